@@ -0,0 +1,263 @@
+use anyhow::Result;
+use clap::Parser as _;
+use clap::Subcommand;
+use d2_stampede::glob_matches;
+use d2_stampede::prelude::*;
+use d2_stampede::proto::*;
+use std::path::PathBuf;
+
+#[derive(clap::Parser)]
+#[command(version, about = "Inspect Dota 2 replays")]
+struct Cli {
+    /// Path to the replay file
+    replay: PathBuf,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Replay info and match summary
+    Info,
+    /// Entities at given tick
+    Entities {
+        /// Tick to stop at, end of the replay by default
+        #[arg(long)]
+        tick: Option<u32>,
+        /// Class name filter, `*` matches any sequence of characters
+        #[arg(long)]
+        class: Option<String>,
+        #[arg(long)]
+        json: bool,
+    },
+    /// Combat log entries
+    Combatlog {
+        #[arg(long)]
+        json: bool,
+    },
+    /// All chat messages
+    Chat,
+    /// String tables at given tick
+    Stringtables {
+        /// Tick to stop at, end of the replay by default
+        #[arg(long)]
+        tick: Option<u32>,
+        /// Print entries of the table with given name
+        #[arg(long)]
+        name: Option<String>,
+    },
+    /// Copy the replay keeping only given tick range
+    Trim {
+        #[arg(long)]
+        from: u32,
+        #[arg(long)]
+        to: u32,
+        /// Path to the new replay
+        #[arg(long, short)]
+        output: PathBuf,
+    },
+}
+
+#[derive(Default)]
+struct CombatLogPrinter {
+    json: bool,
+}
+
+impl Observer for CombatLogPrinter {
+    fn on_combat_log(&mut self, ctx: &Context, combat_log: &CombatLog) -> Result<()> {
+        // Entries with missing values are printed with their raw type
+        let Ok(entry) = combat_log.entry() else {
+            if self.json {
+                let value = serde_json::json!({
+                    "tick": ctx.tick(),
+                    "type": format!("{:?}", combat_log.type_()),
+                });
+                println!("{}", value);
+            } else {
+                println!("{:?}", combat_log.type_());
+            }
+            return Ok(());
+        };
+        if self.json {
+            let value = serde_json::json!({
+                "tick": ctx.tick(),
+                "time": entry.game_time(),
+                "type": format!("{:?}", combat_log.type_()),
+                "attacker": entry.attacker_name(),
+                "target": entry.target_name(),
+                "inflictor": combat_log.inflictor_name().ok(),
+                "value": combat_log.value().ok(),
+            });
+            println!("{}", value);
+        } else {
+            println!("{:.2} {:?}", entry.game_time(), entry);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct ChatPrinter;
+
+impl Observer for ChatPrinter {
+    fn on_dota_user_message(
+        &mut self,
+        ctx: &Context,
+        msg_type: EDotaUserMessages,
+        msg: &[u8],
+    ) -> Result<()> {
+        if msg_type == EDotaUserMessages::DotaUmChatMessage {
+            let message = CdotaUserMsgChatMessage::decode(msg)?;
+            let name: String = ctx
+                .entities()
+                .get_by_class_name("CDOTA_PlayerResource")
+                .ok()
+                .and_then(|pr| {
+                    try_property!(
+                        pr,
+                        "m_vecPlayerData.{:04}.m_iszPlayerName",
+                        message.source_player_id()
+                    )
+                })
+                .unwrap_or_else(|| message.source_player_id().to_string());
+            println!("[{}] {}: {}", ctx.tick(), name, message.message_text());
+        }
+        Ok(())
+    }
+}
+
+fn run(parser: &mut Parser, tick: Option<u32>) -> Result<()> {
+    match tick {
+        Some(tick) => parser.jump_to_tick(tick),
+        None => parser.run_to_end(),
+    }
+}
+
+fn info(replay: &[u8]) -> Result<()> {
+    let header = Parser::header(replay)?;
+    println!("Server: {}", header.server_name());
+    println!("Build: {}", header.build_num());
+
+    let info = Parser::file_info(replay)?;
+    println!("Playback time: {:.2}s", info.playback_time());
+    println!("Playback ticks: {}", info.playback_ticks());
+
+    let Some(dota) = info.game_info.as_ref().and_then(|x| x.dota.as_ref()) else {
+        return Ok(());
+    };
+
+    println!("Match id: {}", dota.match_id());
+    println!("Game mode: {}", dota.game_mode());
+    println!(
+        "Winner: {}",
+        match dota.game_winner() {
+            2 => "Radiant",
+            3 => "Dire",
+            _ => "Unknown",
+        }
+    );
+    if dota.leagueid() != 0 {
+        println!("League id: {}", dota.leagueid());
+        println!(
+            "Teams: {} ({}) vs {} ({})",
+            dota.radiant_team_tag(),
+            dota.radiant_team_id(),
+            dota.dire_team_tag(),
+            dota.dire_team_id()
+        );
+    }
+
+    println!();
+    for player in dota.player_info.iter() {
+        println!(
+            "{:<7} {:<32} {:<20} {}",
+            if player.game_team() == 2 {
+                "Radiant"
+            } else {
+                "Dire"
+            },
+            player.hero_name(),
+            player.steamid(),
+            player.player_name()
+        );
+    }
+
+    Ok(())
+}
+
+fn entities(parser: &mut Parser, tick: Option<u32>, class: Option<&str>, json: bool) -> Result<()> {
+    run(parser, tick)?;
+
+    let entities = parser
+        .context
+        .entities()
+        .iter()
+        .filter(|entity| class.is_none_or(|x| glob_matches(x, entity.class().name())));
+
+    if json {
+        let map = entities
+            .map(|entity| Ok((entity.index().to_string(), serde_json::to_value(entity)?)))
+            .collect::<Result<serde_json::Map<_, _>>>()?;
+        println!("{}", serde_json::to_string_pretty(&map)?);
+    } else {
+        for entity in entities {
+            println!(
+                "{} {} (handle {})\n{}",
+                entity.index(),
+                entity.class().name(),
+                entity.handle(),
+                entity
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn string_tables(parser: &mut Parser, tick: Option<u32>, name: Option<&str>) -> Result<()> {
+    run(parser, tick)?;
+
+    let string_tables = parser.context.string_tables();
+    if let Some(name) = name {
+        println!("{}", string_tables.get_by_name(name)?);
+    } else {
+        println!("{}", string_tables);
+    }
+
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    let file = std::fs::File::open(&cli.replay)?;
+    let replay = unsafe { memmap2::Mmap::map(&file)? };
+    let replay = d2_stampede::decompress(&replay)?;
+    let mut parser = Parser::new(&replay)?;
+
+    match cli.command {
+        Command::Info => info(&replay)?,
+        Command::Entities { tick, class, json } => {
+            entities(&mut parser, tick, class.as_deref(), json)?
+        }
+        Command::Combatlog { json } => {
+            parser
+                .register_observer::<CombatLogPrinter>()
+                .borrow_mut()
+                .json = json;
+            parser.run_to_end()?;
+        }
+        Command::Chat => {
+            parser.register_observer::<ChatPrinter>();
+            parser.run_to_end()?;
+        }
+        Command::Stringtables { tick, name } => string_tables(&mut parser, tick, name.as_deref())?,
+        Command::Trim { from, to, output } => {
+            let file = std::fs::File::create(output)?;
+            d2_stampede::trim_replay(&replay, from, to, std::io::BufWriter::new(file))?;
+        }
+    }
+
+    Ok(())
+}
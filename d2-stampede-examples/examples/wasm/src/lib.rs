@@ -0,0 +1,58 @@
+use d2_stampede::prelude::*;
+use d2_stampede::proto::*;
+use serde_json::{json, Value};
+use wasm_bindgen::prelude::*;
+
+#[derive(Default)]
+struct EventCollector {
+    events: Vec<Value>,
+}
+
+impl Observer for EventCollector {
+    fn on_dota_user_message(
+        &mut self,
+        ctx: &Context,
+        msg_type: EDotaUserMessages,
+        msg: &[u8],
+    ) -> d2_stampede::Result<()> {
+        if msg_type == EDotaUserMessages::DotaUmChatMessage {
+            let message = CdotaUserMsgChatMessage::decode(msg)?;
+            self.events.push(json!({
+                "event": "chat",
+                "tick": ctx.tick(),
+                "player_id": message.source_player_id(),
+                "text": message.message_text(),
+            }));
+        }
+        Ok(())
+    }
+
+    fn on_combat_log(&mut self, ctx: &Context, combat_log: &CombatLog) -> d2_stampede::Result<()> {
+        let Ok(entry) = combat_log.entry() else {
+            return Ok(());
+        };
+        self.events.push(json!({
+            "event": "combat_log",
+            "tick": ctx.tick(),
+            "time": entry.game_time(),
+            "type": format!("{:?}", combat_log.type_()),
+            "attacker": entry.attacker_name(),
+            "target": entry.target_name(),
+        }));
+        Ok(())
+    }
+}
+
+/// Parses replay from given buffer and returns collected events as JSON array.
+#[wasm_bindgen]
+pub fn parse(replay: &[u8]) -> Result<String, JsError> {
+    let mut parser = Parser::new(replay).map_err(|e| JsError::new(&e.to_string()))?;
+    let collector = parser.register_observer::<EventCollector>();
+
+    parser
+        .run_to_end()
+        .map_err(|e| JsError::new(&e.to_string()))?;
+
+    let events = std::mem::take(&mut collector.borrow_mut().events);
+    Ok(serde_json::to_string(&events)?)
+}
@@ -0,0 +1,141 @@
+use anyhow::Result;
+use hashbrown::HashMap;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use d2_stampede::prelude::*;
+use d2_stampede::proto::DotaCombatlogTypes;
+use d2_stampede::try_observers;
+
+use crate::entity_name;
+
+/// Number of `m_hAbilities` slots.
+const ABILITY_SLOTS: usize = 35;
+
+/// Follows `m_hAbilities` of picked heroes and `DOTA_COMBATLOG_ABILITY`
+/// entries. Hero and ability names are the same as in combat log, e.g.
+/// `npc_dota_hero_axe` and `axe_berserkers_call`.
+#[derive(Default)]
+pub struct Abilities {
+    observers: Vec<Rc<RefCell<dyn AbilitiesObserver + 'static>>>,
+
+    levels: HashMap<EHandle, i32>,
+    dirty: bool,
+}
+
+impl Abilities {
+    pub fn register_observer(&mut self, obs: Rc<RefCell<dyn AbilitiesObserver + 'static>>) {
+        self.observers.push(obs)
+    }
+
+    /// Current level of the ability with given handle.
+    pub fn level(&self, ability: &EHandle) -> Option<i32> {
+        self.levels.get(ability).copied()
+    }
+
+    fn update(&mut self, ctx: &Context, hero: &Entity) -> Result<()> {
+        let hero_name = entity_name(ctx, hero)?;
+        for slot in 0..ABILITY_SLOTS {
+            let Some(handle): Option<EHandle> = try_property!(hero, "m_hAbilities.{slot:04}")
+            else {
+                break;
+            };
+            let Ok(ability) = ctx.entities().get(&handle) else {
+                continue;
+            };
+
+            let level: i32 = try_property!(ability, "m_iLevel").unwrap_or_default();
+            let old = self.levels.insert(handle, level).unwrap_or_default();
+            if level > old {
+                let ability_name = entity_name(ctx, ability)?;
+                try_observers!(
+                    self,
+                    on_ability_leveled(ctx, hero_name, ability_name, level)
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Observer for Abilities {
+    fn on_entity(&mut self, _ctx: &Context, event: EntityEvents, entity: &Entity) -> Result<()> {
+        if (event == EntityEvents::Created || event == EntityEvents::Updated)
+            && entity.class().name().contains("Ability")
+        {
+            self.dirty = true;
+        }
+        Ok(())
+    }
+
+    fn combat_log_types(&self) -> CombatLogTypes {
+        CombatLogTypes::new(&[DotaCombatlogTypes::DotaCombatlogAbility])
+    }
+
+    fn on_combat_log(&mut self, ctx: &Context, combat_log: &CombatLog) -> Result<()> {
+        if !combat_log.attacker_hero().unwrap_or_default() {
+            return Ok(());
+        }
+        if let Ok(CombatLogEntry::AbilityUse {
+            attacker,
+            ability,
+            target,
+            ..
+        }) = combat_log.entry()
+        {
+            let (attacker, ability) = (attacker.unwrap_or_default(), ability.unwrap_or_default());
+            try_observers!(self, on_ability_cast(ctx, attacker, ability, target))?;
+        }
+        Ok(())
+    }
+
+    fn on_tick_end(&mut self, ctx: &Context) -> Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+        self.dirty = false;
+
+        let Ok(pr) = ctx.entities().get_by_class_name("CDOTA_PlayerResource") else {
+            return Ok(());
+        };
+
+        let mut player_id = 0;
+        while let Some(handle) =
+            try_property!(pr, "m_vecPlayerTeamData.{player_id:04}.m_hSelectedHero")
+        {
+            if let Ok(hero) = ctx.entities().get(&handle) {
+                self.update(ctx, hero)?;
+            }
+            player_id += 1;
+        }
+
+        Ok(())
+    }
+}
+
+#[allow(unused_variables)]
+pub trait AbilitiesObserver {
+    /// Called when ability of the picked hero gains a level. Abilities that
+    /// are learned at the start of the game (e.g. innate abilities) are
+    /// reported with their initial level.
+    fn on_ability_leveled(
+        &mut self,
+        ctx: &Context,
+        hero: &str,
+        ability: &str,
+        level: i32,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called for `DOTA_COMBATLOG_ABILITY` entries of heroes.
+    fn on_ability_cast(
+        &mut self,
+        ctx: &Context,
+        hero: &str,
+        ability: &str,
+        target: Option<&str>,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,199 @@
+use anyhow::Result;
+use hashbrown::HashMap;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use d2_stampede::prelude::*;
+use d2_stampede::proto::DotaCombatlogTypes;
+use d2_stampede::try_observers;
+
+use crate::entity_name;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum BuildingKind {
+    Tower,
+    MeleeBarracks,
+    RangedBarracks,
+    Outpost,
+    Ancient,
+}
+
+impl BuildingKind {
+    fn from_class_name(class: &str, name: &str) -> Option<Self> {
+        match class {
+            "CDOTA_BaseNPC_Tower" => Some(BuildingKind::Tower),
+            "CDOTA_BaseNPC_Barracks" if name.contains("melee") => Some(BuildingKind::MeleeBarracks),
+            "CDOTA_BaseNPC_Barracks" => Some(BuildingKind::RangedBarracks),
+            "CDOTA_BaseNPC_Watch_Tower" => Some(BuildingKind::Outpost),
+            "CDOTA_BaseNPC_Fort" => Some(BuildingKind::Ancient),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Lane {
+    Top,
+    Mid,
+    Bot,
+}
+
+impl Lane {
+    fn from_name(name: &str) -> Option<Self> {
+        if name.ends_with("_top") {
+            Some(Lane::Top)
+        } else if name.ends_with("_mid") {
+            Some(Lane::Mid)
+        } else if name.ends_with("_bot") {
+            Some(Lane::Bot)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Building {
+    pub kind: BuildingKind,
+    /// Entity name, e.g. `npc_dota_goodguys_tower1_top`. Same as the combat
+    /// log name of the building.
+    pub name: Box<str>,
+    pub index: u32,
+    pub team: i32,
+    pub lane: Option<Lane>,
+    /// Tower tier, `4` for towers near the ancient.
+    pub tier: Option<u32>,
+    pub health: i32,
+    pub max_health: i32,
+    pub position: Option<[f32; 3]>,
+}
+
+impl Building {
+    fn new(ctx: &Context, entity: &Entity) -> Result<Option<Self>> {
+        let name = entity_name(ctx, entity)?;
+        let Some(kind) = BuildingKind::from_class_name(entity.class().name(), name) else {
+            return Ok(None);
+        };
+        let tier = name
+            .split_once("tower")
+            .and_then(|(_, rest)| rest.chars().next())
+            .and_then(|c| c.to_digit(10));
+        Ok(Some(Building {
+            kind,
+            name: name.into(),
+            index: entity.index(),
+            team: property!(entity, "m_iTeamNum"),
+            lane: Lane::from_name(name),
+            tier: if kind == BuildingKind::Tower {
+                tier
+            } else {
+                None
+            },
+            health: property!(entity, "m_iHealth"),
+            max_health: property!(entity, "m_iMaxHealth"),
+            position: entity.position(),
+        }))
+    }
+}
+
+/// Standing towers, barracks, outposts and ancients by entity index.
+/// Buildings are removed when they are destroyed.
+#[derive(Default)]
+pub struct Buildings {
+    observers: Vec<Rc<RefCell<dyn BuildingsObserver + 'static>>>,
+
+    buildings: HashMap<u32, Building>,
+}
+
+impl Buildings {
+    pub fn register_observer(&mut self, obs: Rc<RefCell<dyn BuildingsObserver + 'static>>) {
+        self.observers.push(obs)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Building> {
+        self.buildings.values()
+    }
+
+    /// Standing buildings of given team.
+    pub fn standing(&self, team: i32) -> impl Iterator<Item = &Building> {
+        self.iter().filter(move |building| building.team == team)
+    }
+
+    pub fn get_by_name(&self, name: &str) -> Option<&Building> {
+        self.iter().find(|building| building.name.as_ref() == name)
+    }
+}
+
+impl Observer for Buildings {
+    fn on_entity(&mut self, ctx: &Context, event: EntityEvents, entity: &Entity) -> Result<()> {
+        if !matches!(
+            entity.class().name(),
+            "CDOTA_BaseNPC_Tower"
+                | "CDOTA_BaseNPC_Barracks"
+                | "CDOTA_BaseNPC_Watch_Tower"
+                | "CDOTA_BaseNPC_Fort"
+        ) {
+            return Ok(());
+        }
+        match event {
+            EntityEvents::Created | EntityEvents::Updated => {
+                let life_state: i32 = try_property!(entity, "m_lifeState").unwrap_or_default();
+                if life_state != 0 {
+                    return Ok(());
+                }
+                if let Some(building) = Building::new(ctx, entity)? {
+                    self.buildings.insert(entity.index(), building);
+                }
+            }
+            EntityEvents::Deleted => {
+                self.buildings.remove(&entity.index());
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn combat_log_types(&self) -> CombatLogTypes {
+        CombatLogTypes::new(&[DotaCombatlogTypes::DotaCombatlogDeath])
+    }
+
+    fn on_combat_log(&mut self, ctx: &Context, combat_log: &CombatLog) -> Result<()> {
+        if !combat_log.target_building().unwrap_or_default() {
+            return Ok(());
+        }
+        let Ok(CombatLogEntry::Death {
+            time,
+            target: Some(target),
+            ..
+        }) = combat_log.entry()
+        else {
+            return Ok(());
+        };
+        let Some(index) = self.get_by_name(target).map(|building| building.index) else {
+            return Ok(());
+        };
+        if let Some(building) = self.buildings.remove(&index) {
+            let attacker_team = combat_log.attacker_team().unwrap_or_default() as i32;
+            try_observers!(
+                self,
+                on_building_destroyed(ctx, &building, attacker_team, time)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[allow(unused_variables)]
+pub trait BuildingsObserver {
+    /// Called for building deaths from combat log. `attacker_team` equal to
+    /// the building team means the building was denied.
+    fn on_building_destroyed(
+        &mut self,
+        ctx: &Context,
+        building: &Building,
+        attacker_team: i32,
+        time: f32,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
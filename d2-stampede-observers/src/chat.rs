@@ -18,27 +18,34 @@ impl Chat {
 }
 
 impl Observer for Chat {
-    fn on_dota_user_message(
+    fn user_messages(&mut self) -> Option<&mut dyn UserMessageObserver> {
+        Some(self)
+    }
+}
+
+impl UserMessageObserver for Chat {
+    fn on_chat_event(
+        &mut self,
+        ctx: &Context,
+        msg: &LazyMessage<CdotaUserMsgChatEvent>,
+    ) -> Result<()> {
+        try_observers!(self, on_chat_event(ctx, msg.get()?))
+    }
+
+    fn on_chat_message(
+        &mut self,
+        ctx: &Context,
+        msg: &LazyMessage<CdotaUserMsgChatMessage>,
+    ) -> Result<()> {
+        try_observers!(self, on_all_chat_message(ctx, msg.get()?))
+    }
+
+    fn on_chat_wheel(
         &mut self,
         ctx: &Context,
-        msg_type: EDotaUserMessages,
-        msg: &[u8],
+        msg: &LazyMessage<CdotaUserMsgChatWheel>,
     ) -> Result<()> {
-        match msg_type {
-            EDotaUserMessages::DotaUmChatEvent => {
-                let chat_event = CdotaUserMsgChatEvent::decode(msg)?;
-                try_observers!(self, on_chat_event(ctx, &chat_event))
-            }
-            EDotaUserMessages::DotaUmChatMessage => {
-                let chat_msg = CdotaUserMsgChatMessage::decode(msg)?;
-                try_observers!(self, on_all_chat_message(ctx, &chat_msg))
-            }
-            EDotaUserMessages::DotaUmChatWheel => {
-                let chat_wheel = CdotaUserMsgChatWheel::decode(msg)?;
-                try_observers!(self, on_chat_wheel(ctx, &chat_wheel))
-            }
-            _ => Ok(()),
-        }
+        try_observers!(self, on_chat_wheel(ctx, msg.get()?))
     }
 }
 
@@ -0,0 +1,178 @@
+use anyhow::Result;
+use hashbrown::HashSet;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use d2_stampede::prelude::*;
+use d2_stampede::proto::*;
+use d2_stampede::try_observers;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct DraftEvent {
+    pub is_pick: bool,
+    pub team: i32,
+    pub hero_id: i32,
+    /// Position of the event in the draft, starting from `0`.
+    pub order: u32,
+}
+
+#[cfg(feature = "names")]
+impl DraftEvent {
+    /// Hero name from the embedded table, `None` for unknown ids.
+    pub fn hero(&self) -> Option<&'static d2_stampede::HeroName> {
+        d2_stampede::Names::hero(self.hero_id)
+    }
+}
+
+/// Picks and bans in draft order.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Draft {
+    pub events: Vec<DraftEvent>,
+}
+
+impl Draft {
+    /// Draft from `picks_bans` of the replay file info. Available from the
+    /// start of the parsing, but may be missing for some game modes.
+    pub fn from_replay_info(info: &CDemoFileInfo) -> Option<Self> {
+        let dota = info.game_info.as_ref()?.dota.as_ref()?;
+        if dota.picks_bans.is_empty() {
+            return None;
+        }
+        let events = dota
+            .picks_bans
+            .iter()
+            .enumerate()
+            .map(|(order, event)| DraftEvent {
+                is_pick: event.is_pick(),
+                team: event.team() as i32,
+                hero_id: event.hero_id() as i32,
+                order: order as u32,
+            })
+            .collect();
+        Some(Draft { events })
+    }
+
+    pub fn picks(&self, team: i32) -> impl Iterator<Item = &DraftEvent> {
+        self.events
+            .iter()
+            .filter(move |event| event.is_pick && event.team == team)
+    }
+
+    pub fn bans(&self, team: i32) -> impl Iterator<Item = &DraftEvent> {
+        self.events
+            .iter()
+            .filter(move |event| !event.is_pick && event.team == team)
+    }
+}
+
+/// Follows `m_SelectedHeroes` and `m_BannedHeroes` arrays of
+/// `CDOTAGamerulesProxy`. Team of the pick is taken from the player who
+/// selected the hero. In Captains Mode picked heroes are not assigned to
+/// players yet, so like for bans it's the team that was active before the
+/// pick appeared.
+#[derive(Default)]
+pub struct HeroSelection {
+    observers: Vec<Rc<RefCell<dyn HeroSelectionObserver + 'static>>>,
+
+    draft: Draft,
+    picked: HashSet<i32>,
+    banned: HashSet<i32>,
+    active_team: i32,
+    dirty: bool,
+}
+
+impl HeroSelection {
+    pub fn register_observer(&mut self, obs: Rc<RefCell<dyn HeroSelectionObserver + 'static>>) {
+        self.observers.push(obs)
+    }
+
+    /// Draft collected so far.
+    pub fn draft(&self) -> &Draft {
+        &self.draft
+    }
+
+    fn picking_team(ctx: &Context, hero_id: i32) -> Result<Option<i32>> {
+        let pr = ctx.entities().get_by_class_name("CDOTA_PlayerResource")?;
+        let mut player_id = 0;
+        while let Some(id) =
+            try_property!(pr, "m_vecPlayerTeamData.{player_id:04}.m_nSelectedHeroID")
+        {
+            if hero_id == id {
+                return Ok(Some(property!(
+                    pr,
+                    "m_vecPlayerData.{player_id:04}.m_iPlayerTeam"
+                )));
+            }
+            player_id += 1;
+        }
+        Ok(None)
+    }
+
+    fn push(&mut self, ctx: &Context, is_pick: bool, team: i32, hero_id: i32) -> Result<()> {
+        let event = DraftEvent {
+            is_pick,
+            team,
+            hero_id,
+            order: self.draft.events.len() as u32,
+        };
+        self.draft.events.push(event);
+        if is_pick {
+            try_observers!(self, on_pick(ctx, team, hero_id, event.order))
+        } else {
+            try_observers!(self, on_ban(ctx, team, hero_id, event.order))
+        }
+    }
+}
+
+impl Observer for HeroSelection {
+    fn on_entity(&mut self, _ctx: &Context, event: EntityEvents, entity: &Entity) -> Result<()> {
+        if (event == EntityEvents::Created || event == EntityEvents::Updated)
+            && entity.class().name() == "CDOTAGamerulesProxy"
+        {
+            self.dirty = true;
+        }
+        Ok(())
+    }
+
+    fn on_tick_end(&mut self, ctx: &Context) -> Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+        self.dirty = false;
+
+        let game_rules = ctx.game_rules()?;
+        let entity = game_rules.entity();
+
+        let mut idx = 0;
+        while let Some(hero_id) = try_property!(entity, "m_pGameRules.m_BannedHeroes.{idx:04}") {
+            if hero_id > 0 && self.banned.insert(hero_id) {
+                self.push(ctx, false, self.active_team, hero_id)?;
+            }
+            idx += 1;
+        }
+
+        let mut idx = 0;
+        while let Some(hero_id) = try_property!(entity, "m_pGameRules.m_SelectedHeroes.{idx:04}") {
+            if hero_id > 0 && self.picked.insert(hero_id) {
+                let team = Self::picking_team(ctx, hero_id)?.unwrap_or(self.active_team);
+                self.push(ctx, true, team, hero_id)?;
+            }
+            idx += 1;
+        }
+
+        self.active_team = try_property!(entity, "m_pGameRules.m_iActiveTeam").unwrap_or_default();
+
+        Ok(())
+    }
+}
+
+#[allow(unused_variables)]
+pub trait HeroSelectionObserver {
+    fn on_pick(&mut self, ctx: &Context, team: i32, hero_id: i32, order: u32) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_ban(&mut self, ctx: &Context, team: i32, hero_id: i32, order: u32) -> Result<()> {
+        Ok(())
+    }
+}
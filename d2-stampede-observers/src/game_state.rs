@@ -18,6 +18,10 @@ impl GameState {
 }
 
 impl Observer for GameState {
+    fn combat_log_types(&self) -> CombatLogTypes {
+        CombatLogTypes::new(&[DotaCombatlogTypes::DotaCombatlogGameState])
+    }
+
     fn on_combat_log(&mut self, ctx: &Context, combat_log: &CombatLog) -> Result<()> {
         if combat_log.type_() == DotaCombatlogTypes::DotaCombatlogGameState {
             let state = DotaGameState::try_from(combat_log.value()? as i32)?;
@@ -0,0 +1,271 @@
+use anyhow::Result;
+use hashbrown::{HashMap, HashSet};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use d2_stampede::prelude::*;
+use d2_stampede::proto::DotaCombatlogTypes;
+use d2_stampede::try_observers;
+
+use crate::entity_name;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum SlotKind {
+    /// Slots `0..6`.
+    Inventory,
+    /// Slots `6..9`.
+    Backpack,
+    /// Slots `9..15`.
+    Stash,
+    /// Slot `15`.
+    Teleport,
+    /// Slot `16`.
+    Neutral,
+    /// Slot `17`, enchantment of the neutral item.
+    NeutralEnchantment,
+}
+
+impl SlotKind {
+    /// Kind of `m_hItems` slot, `None` for transient slots after the
+    /// neutral ones.
+    pub fn from_slot(slot: usize) -> Option<Self> {
+        match slot {
+            0..=5 => Some(SlotKind::Inventory),
+            6..=8 => Some(SlotKind::Backpack),
+            9..=14 => Some(SlotKind::Stash),
+            15 => Some(SlotKind::Teleport),
+            16 => Some(SlotKind::Neutral),
+            17 => Some(SlotKind::NeutralEnchantment),
+            _ => None,
+        }
+    }
+
+    pub fn is_neutral(self) -> bool {
+        matches!(self, SlotKind::Neutral | SlotKind::NeutralEnchantment)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Item {
+    /// Item name, e.g. `item_blink`.
+    pub name: Box<str>,
+    pub handle: EHandle,
+    pub charges: i32,
+}
+
+impl Item {
+    pub(crate) fn new(ctx: &Context, entity: &Entity) -> Result<Self> {
+        Ok(Item {
+            name: entity_name(ctx, entity)?.into(),
+            handle: EHandle::new(entity.handle()),
+            charges: try_property!(entity, "m_iCurrentCharges").unwrap_or_default(),
+        })
+    }
+}
+
+/// Items of the player's hero by `m_hItems` slot. Number of slots is taken
+/// from the hero entity and depends on the game build.
+#[derive(Debug, Clone, Default)]
+pub struct Inventory {
+    pub slots: Vec<Option<Item>>,
+}
+
+impl Inventory {
+    pub fn get(&self, slot: usize) -> Option<&Item> {
+        self.slots.get(slot).and_then(|item| item.as_ref())
+    }
+
+    /// Occupied slots of given kind with their items.
+    pub fn iter_kind(&self, kind: SlotKind) -> impl Iterator<Item = (usize, &Item)> {
+        self.iter()
+            .filter(move |(slot, _)| SlotKind::from_slot(*slot) == Some(kind))
+    }
+
+    /// Occupied slots with their items.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &Item)> {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(slot, item)| item.as_ref().map(|item| (slot, item)))
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.iter().any(|(_, item)| item.name.as_ref() == name)
+    }
+}
+
+/// Follows `m_hItems` of picked heroes and `DOTA_COMBATLOG_PURCHASE` entries.
+/// Inventories are compared at the end of the tick, removed items are
+/// reported as consumed if the item entity no longer exists and as dropped
+/// otherwise (dropped on the ground, moved to another unit or sold).
+#[derive(Default)]
+pub struct Inventories {
+    observers: Vec<Rc<RefCell<dyn InventoryObserver + 'static>>>,
+
+    inventories: HashMap<i32, Inventory>,
+    changed_heroes: HashSet<u32>,
+}
+
+impl Inventories {
+    pub fn register_observer(&mut self, obs: Rc<RefCell<dyn InventoryObserver + 'static>>) {
+        self.observers.push(obs)
+    }
+
+    /// Inventory of the player with given id.
+    pub fn get(&self, player_id: i32) -> Option<&Inventory> {
+        self.inventories.get(&player_id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (i32, &Inventory)> {
+        self.inventories
+            .iter()
+            .map(|(&player_id, inventory)| (player_id, inventory))
+    }
+
+    fn update(&mut self, ctx: &Context, hero: &Entity, time: f32) -> Result<()> {
+        let Some(player_id): Option<i32> = try_property!(hero, "m_iPlayerID") else {
+            return Ok(());
+        };
+
+        // Skip illusions and other units sharing the player id
+        let Some(selected) = ctx
+            .player_resource()
+            .and_then(|pr| pr.selected_hero(player_id as usize))
+            .ok()
+        else {
+            return Ok(());
+        };
+        if selected.index() != hero.index() {
+            return Ok(());
+        }
+
+        let Ok(handles) = hero.get_array::<EHandle>("m_hItems") else {
+            return Ok(());
+        };
+        let inventory = Inventory {
+            slots: handles
+                .iter()
+                .map(|handle| {
+                    ctx.entities()
+                        .get(handle)
+                        .ok()
+                        .and_then(|entity| Item::new(ctx, entity).ok())
+                })
+                .collect(),
+        };
+
+        let old = self.inventories.remove(&player_id).unwrap_or_default();
+
+        for (slot, item) in old.iter() {
+            if inventory.iter().any(|(_, x)| x.handle == item.handle) {
+                continue;
+            }
+            if ctx.entities().get(&item.handle).is_ok() {
+                try_observers!(self, on_item_dropped(ctx, player_id, item, slot, time))?;
+            } else {
+                try_observers!(self, on_item_consumed(ctx, player_id, item, slot, time))?;
+            }
+        }
+
+        for (slot, item) in inventory.iter() {
+            if old.get(slot).is_none_or(|x| x.handle != item.handle) {
+                try_observers!(
+                    self,
+                    on_item_added_to_slot(ctx, player_id, item, slot, time)
+                )?;
+            }
+        }
+
+        self.inventories.insert(player_id, inventory);
+
+        Ok(())
+    }
+}
+
+impl Observer for Inventories {
+    fn on_entity(&mut self, _ctx: &Context, event: EntityEvents, entity: &Entity) -> Result<()> {
+        if (event == EntityEvents::Created || event == EntityEvents::Updated)
+            && entity.class().name().starts_with("CDOTA_Unit_Hero_")
+        {
+            self.changed_heroes.insert(entity.index());
+        }
+        Ok(())
+    }
+
+    fn combat_log_types(&self) -> CombatLogTypes {
+        CombatLogTypes::new(&[DotaCombatlogTypes::DotaCombatlogPurchase])
+    }
+
+    fn on_combat_log(&mut self, ctx: &Context, combat_log: &CombatLog) -> Result<()> {
+        if let Ok(CombatLogEntry::ItemPurchase { time, buyer, item }) = combat_log.entry() {
+            let (buyer, item) = (buyer.unwrap_or_default(), item.unwrap_or_default());
+            try_observers!(self, on_item_purchased(ctx, buyer, item, time))?;
+        }
+        Ok(())
+    }
+
+    fn on_tick_end(&mut self, ctx: &Context) -> Result<()> {
+        if self.changed_heroes.is_empty() {
+            return Ok(());
+        }
+
+        let time = ctx.game_time().unwrap_or_default();
+
+        for index in std::mem::take(&mut self.changed_heroes) {
+            if let Ok(hero) = ctx.entities().get_by_index(index as usize) {
+                self.update(ctx, hero, time)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[allow(unused_variables)]
+pub trait InventoryObserver {
+    /// Called for `DOTA_COMBATLOG_PURCHASE` entries. `buyer` is the combat
+    /// log name of the hero, e.g. `npc_dota_hero_axe`.
+    fn on_item_purchased(
+        &mut self,
+        ctx: &Context,
+        buyer: &str,
+        item: &str,
+        time: f32,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called when item appears in the slot, including moves between slots.
+    fn on_item_added_to_slot(
+        &mut self,
+        ctx: &Context,
+        player_id: i32,
+        item: &Item,
+        slot: usize,
+        time: f32,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_item_consumed(
+        &mut self,
+        ctx: &Context,
+        player_id: i32,
+        item: &Item,
+        slot: usize,
+        time: f32,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_item_dropped(
+        &mut self,
+        ctx: &Context,
+        player_id: i32,
+        item: &Item,
+        slot: usize,
+        time: f32,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
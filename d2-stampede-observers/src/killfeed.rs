@@ -0,0 +1,173 @@
+use anyhow::Result;
+use hashbrown::HashMap;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use d2_stampede::prelude::*;
+use d2_stampede::try_observers;
+
+/// `DOTA_ModifyGold_HeroKill`
+const GOLD_REASON_HERO_KILL: u32 = 12;
+/// `DOTA_ModifyXP_HeroKill`
+const XP_REASON_HERO_KILL: u32 = 1;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum KillKind {
+    Kill,
+    /// Hero killed by an ally.
+    Deny,
+    /// Hero killed itself.
+    Suicide,
+}
+
+#[derive(Debug, Clone)]
+pub struct HeroKill {
+    pub time: f32,
+    /// Combat log name of the killed hero.
+    pub victim: Box<str>,
+    /// Combat log name of the attacker, not necessarily a hero (towers,
+    /// creeps, neutrals).
+    pub killer: Box<str>,
+    /// Player ids of assisting players.
+    pub assists: Vec<i32>,
+    pub kind: KillKind,
+    /// Number of kills the victim made since its previous death.
+    pub streak_ended: u32,
+    /// Gold awarded for the kill by hero combat log name.
+    pub gold: HashMap<Box<str>, u32>,
+    /// Experience awarded for the kill by hero combat log name.
+    pub xp: HashMap<Box<str>, u32>,
+}
+
+#[cfg(feature = "names")]
+impl HeroKill {
+    /// English name of the killed hero.
+    pub fn victim_display_name(&self) -> &str {
+        d2_stampede::Names::localized(&self.victim)
+    }
+
+    /// English name of the killer if it's a hero, combat log name otherwise.
+    pub fn killer_display_name(&self) -> &str {
+        d2_stampede::Names::localized(&self.killer)
+    }
+}
+
+/// Builds hero kills from combat log deaths. Gold and experience for the kill
+/// are logged after the death in the same tick, so kills are emitted at the
+/// end of the tick.
+#[derive(Default)]
+pub struct Killfeed {
+    observers: Vec<Rc<RefCell<dyn KillfeedObserver + 'static>>>,
+
+    pending: Vec<HeroKill>,
+    streaks: HashMap<Box<str>, u32>,
+}
+
+impl Killfeed {
+    pub fn register_observer(&mut self, obs: Rc<RefCell<dyn KillfeedObserver + 'static>>) {
+        self.observers.push(obs)
+    }
+
+    /// Current kill streak of the hero with given combat log name.
+    pub fn streak(&self, hero: &str) -> u32 {
+        self.streaks.get(hero).copied().unwrap_or_default()
+    }
+
+    /// Kill awarding gold or experience to given hero: the kill made by the
+    /// hero, or the only kill of the tick (assisting heroes). Rewards that
+    /// can't be matched to a kill are ignored.
+    fn awarded_kill(&mut self, hero: &str) -> Option<&mut HeroKill> {
+        let idx = match self
+            .pending
+            .iter()
+            .position(|kill| kill.killer.as_ref() == hero)
+        {
+            Some(idx) => idx,
+            None if self.pending.len() == 1 => 0,
+            None => return None,
+        };
+        self.pending.get_mut(idx)
+    }
+}
+
+impl Observer for Killfeed {
+    fn on_combat_log(&mut self, _ctx: &Context, combat_log: &CombatLog) -> Result<()> {
+        let Ok(entry) = combat_log.entry() else {
+            return Ok(());
+        };
+
+        match entry {
+            CombatLogEntry::Death {
+                time,
+                attacker,
+                target,
+                target_hero: true,
+                target_illusion: false,
+                ..
+            } => {
+                let kind = if attacker.is_some() && attacker == target {
+                    KillKind::Suicide
+                } else if combat_log.attacker_team().ok() == combat_log.target_team().ok() {
+                    KillKind::Deny
+                } else {
+                    KillKind::Kill
+                };
+
+                if let Some(attacker) = attacker.filter(|_| {
+                    kind == KillKind::Kill && combat_log.attacker_hero().unwrap_or_default()
+                }) {
+                    *self.streaks.entry(attacker.into()).or_default() += 1;
+                }
+
+                self.pending.push(HeroKill {
+                    time,
+                    victim: target.unwrap_or_default().into(),
+                    killer: attacker.unwrap_or_default().into(),
+                    assists: combat_log.assist_players().to_vec(),
+                    kind,
+                    streak_ended: target
+                        .and_then(|target| self.streaks.remove(target))
+                        .unwrap_or_default(),
+                    gold: HashMap::default(),
+                    xp: HashMap::default(),
+                });
+            }
+            CombatLogEntry::GoldChange {
+                target: Some(target),
+                amount,
+                reason: GOLD_REASON_HERO_KILL,
+                ..
+            } => {
+                if let Some(kill) = self.awarded_kill(target) {
+                    *kill.gold.entry(target.into()).or_default() += amount.unsigned_abs();
+                }
+            }
+            CombatLogEntry::XpGain {
+                target: Some(target),
+                amount,
+                reason: XP_REASON_HERO_KILL,
+                ..
+            } => {
+                if let Some(kill) = self.awarded_kill(target) {
+                    *kill.xp.entry(target.into()).or_default() += amount;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn on_tick_end(&mut self, ctx: &Context) -> Result<()> {
+        for kill in std::mem::take(&mut self.pending) {
+            try_observers!(self, on_hero_kill(ctx, &kill))?;
+        }
+        Ok(())
+    }
+}
+
+#[allow(unused_variables)]
+pub trait KillfeedObserver {
+    fn on_hero_kill(&mut self, ctx: &Context, kill: &HeroKill) -> Result<()> {
+        Ok(())
+    }
+}
@@ -1,5 +1,45 @@
+pub mod abilities;
+pub mod buildings;
 pub mod chat;
+pub mod draft;
 pub mod game_state;
 pub mod game_time;
+pub mod inventory;
+pub mod killfeed;
+pub mod modifiers;
+pub mod neutral_items;
+pub mod particles;
 pub mod players;
+pub mod positions;
+pub mod smoke;
+pub mod spectator;
+pub mod teamfights;
+pub mod time_series;
 pub mod wards;
+
+use d2_stampede::prelude::*;
+
+/// Entity name from `EntityNames` string table, e.g. `npc_dota_hero_axe` or
+/// `item_blink`. Matches names used in combat log.
+pub(crate) fn entity_name<'a>(ctx: &'a Context, entity: &Entity) -> anyhow::Result<&'a str> {
+    let name_idx: i32 = property!(entity, "m_pEntity.m_nameStringableIndex");
+    Ok(ctx
+        .string_tables()
+        .get_by_name("EntityNames")?
+        .get_entry_by_index(name_idx as usize)?
+        .key())
+}
+
+/// Position of the hero with given combat log name.
+pub(crate) fn hero_position(ctx: &Context, name: &str) -> Option<[f32; 3]> {
+    ctx.entities()
+        .iter()
+        .filter(|entity| entity.class().name().starts_with("CDOTA_Unit_Hero_"))
+        .find(|&entity| entity_name(ctx, entity).is_ok_and(|x| x == name))
+        .and_then(|entity| entity.position())
+}
+
+/// Distance between two world positions on the map plane.
+pub(crate) fn distance(a: [f32; 3], b: [f32; 3]) -> f32 {
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2)).sqrt()
+}
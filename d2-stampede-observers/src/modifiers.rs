@@ -0,0 +1,158 @@
+use anyhow::Result;
+use hashbrown::HashMap;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use d2_stampede::prelude::*;
+use d2_stampede::proto::*;
+use d2_stampede::try_observers;
+
+#[derive(Debug, Clone)]
+pub struct Modifier {
+    pub name: Box<str>,
+    pub index: i32,
+    pub serial_num: i32,
+    pub parent: u32,
+    pub caster: u32,
+    pub ability: u32,
+    pub ability_level: i32,
+    pub stack_count: i32,
+    pub creation_time: f32,
+    pub duration: f32,
+    pub aura: bool,
+}
+
+impl Modifier {
+    /// Game time when the modifier expires, `None` for modifiers without
+    /// duration.
+    pub fn end_time(&self) -> Option<f32> {
+        (self.duration >= 0.0).then_some(self.creation_time + self.duration)
+    }
+}
+
+/// Tracks buffs and debuffs from `ActiveModifiers` string table.
+#[derive(Default)]
+pub struct Modifiers {
+    observers: Vec<Rc<RefCell<dyn ModifiersObserver + 'static>>>,
+
+    active: HashMap<u32, HashMap<i32, Modifier>>,
+}
+
+impl Modifiers {
+    pub fn register_observer(&mut self, obs: Rc<RefCell<dyn ModifiersObserver + 'static>>) {
+        self.observers.push(obs)
+    }
+
+    /// Active modifiers of the entity with given handle.
+    pub fn get_by_parent(&self, handle: u32) -> impl Iterator<Item = &Modifier> {
+        self.active
+            .get(&(handle & 0x3fff))
+            .into_iter()
+            .flat_map(|modifiers| modifiers.values())
+    }
+
+    pub fn has_modifier(&self, handle: u32, name: &str) -> bool {
+        self.get_by_parent(handle)
+            .any(|modifier| modifier.name.as_ref() == name)
+    }
+
+    fn modifier_name(ctx: &Context, entry: &CdotaModifierBuffTableEntry) -> Box<str> {
+        entry
+            .modifier_class
+            .and_then(|class| {
+                ctx.string_tables()
+                    .get_by_name("ModifierNames")
+                    .ok()?
+                    .get_entry_by_index(class as usize)
+                    .ok()
+                    .map(|entry| entry.key().into())
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl Observer for Modifiers {
+    fn on_string_table_update(
+        &mut self,
+        ctx: &Context,
+        table: &StringTable,
+        modified: &[i32],
+    ) -> Result<()> {
+        if table.name() != "ActiveModifiers" {
+            return Ok(());
+        }
+
+        for &idx in modified {
+            let Some(value) = table.get_entry_by_index(idx as usize)?.value() else {
+                continue;
+            };
+
+            let entry = CdotaModifierBuffTableEntry::decode(value)?;
+
+            match entry.entry_type() {
+                DotaModifierEntryType::Active => {
+                    let modifier = Modifier {
+                        name: Self::modifier_name(ctx, &entry),
+                        index: entry.index,
+                        serial_num: entry.serial_num,
+                        parent: entry.parent,
+                        caster: entry.caster(),
+                        ability: entry.ability(),
+                        ability_level: entry.ability_level(),
+                        stack_count: entry.stack_count(),
+                        creation_time: entry.creation_time(),
+                        duration: entry.duration(),
+                        aura: entry.aura(),
+                    };
+
+                    let modifiers = self.active.entry(entry.parent & 0x3fff).or_default();
+                    let added = modifiers
+                        .get(&entry.index)
+                        .is_none_or(|x| x.serial_num != entry.serial_num);
+                    modifiers.insert(entry.index, modifier.clone());
+
+                    if added {
+                        try_observers!(self, on_modifier_added(ctx, &modifier))?;
+                    } else {
+                        try_observers!(self, on_modifier_updated(ctx, &modifier))?;
+                    }
+                }
+                DotaModifierEntryType::Removed => {
+                    if let Some(modifier) = self
+                        .active
+                        .get_mut(&(entry.parent & 0x3fff))
+                        .and_then(|modifiers| modifiers.remove(&entry.index))
+                    {
+                        try_observers!(self, on_modifier_removed(ctx, &modifier))?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn on_entity(&mut self, _ctx: &Context, event: EntityEvents, entity: &Entity) -> Result<()> {
+        if event == EntityEvents::Deleted {
+            self.active.remove(&entity.index());
+        }
+        Ok(())
+    }
+}
+
+#[allow(unused_variables)]
+pub trait ModifiersObserver {
+    fn on_modifier_added(&mut self, ctx: &Context, modifier: &Modifier) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called when stack count, duration or other property of active modifier
+    /// changes.
+    fn on_modifier_updated(&mut self, ctx: &Context, modifier: &Modifier) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_modifier_removed(&mut self, ctx: &Context, modifier: &Modifier) -> Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,313 @@
+use anyhow::Result;
+use hashbrown::{HashMap, HashSet};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use d2_stampede::prelude::*;
+use d2_stampede::proto::CdotaUserMsgFoundNeutralItem;
+use d2_stampede::try_observers;
+
+use crate::inventory::{Item, SlotKind};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NeutralItemDrop {
+    pub player_id: i32,
+    /// Ability id of the item, same as in `DOTA_UM_FoundNeutralItem`.
+    pub item_id: i32,
+    pub tier: u32,
+    /// Number of items of this tier the team has found, including this one.
+    pub tier_count: u32,
+    pub time: f32,
+}
+
+/// Neutral slot with old and new item.
+type SlotChange = (SlotKind, Option<Item>, Option<Item>);
+
+/// Where an item went after leaving the neutral slot.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum NeutralItemLocation {
+    /// `m_hItems` slot of a picked hero.
+    Hero { player_id: i32, slot: usize },
+    /// Item entity exists but no picked hero holds it, which is the neutral
+    /// stash unless the item was dropped on the ground.
+    Stash,
+}
+
+/// Tracks neutral item drops from `DOTA_UM_FoundNeutralItem` and both neutral
+/// slots of picked heroes. Items removed from a neutral slot are reported as
+/// consumed if the item entity no longer exists, otherwise as unequipped and
+/// then followed through inventories of picked heroes and the stash until
+/// they are equipped again or destroyed.
+#[derive(Default)]
+pub struct NeutralItems {
+    observers: Vec<Rc<RefCell<dyn NeutralItemsObserver + 'static>>>,
+
+    drops: Vec<NeutralItemDrop>,
+    tier_times: BTreeMap<u32, f32>,
+    equipped: HashMap<(i32, SlotKind), Item>,
+    /// Items in `m_hItems` of picked heroes with player id and slot.
+    held: HashMap<EHandle, (i32, usize)>,
+    unequipped: HashMap<EHandle, (Item, NeutralItemLocation)>,
+    changed_heroes: HashSet<u32>,
+}
+
+impl NeutralItems {
+    pub fn register_observer(&mut self, obs: Rc<RefCell<dyn NeutralItemsObserver + 'static>>) {
+        self.observers.push(obs)
+    }
+
+    /// All drops in order.
+    pub fn drops(&self) -> &[NeutralItemDrop] {
+        &self.drops
+    }
+
+    /// Game time of the first drop of given tier.
+    pub fn tier_time(&self, tier: u32) -> Option<f32> {
+        self.tier_times.get(&tier).copied()
+    }
+
+    /// Neutral item of the player with given id.
+    pub fn equipped(&self, player_id: i32) -> Option<&Item> {
+        self.equipped.get(&(player_id, SlotKind::Neutral))
+    }
+
+    /// Enchantment of the neutral item of the player with given id.
+    pub fn enchantment(&self, player_id: i32) -> Option<&Item> {
+        self.equipped
+            .get(&(player_id, SlotKind::NeutralEnchantment))
+    }
+
+    /// Unequipped items and where they are now.
+    pub fn unequipped(&self) -> impl Iterator<Item = (&Item, NeutralItemLocation)> {
+        self.unequipped
+            .values()
+            .map(|(item, location)| (item, *location))
+    }
+
+    fn locate(&self, handle: &EHandle) -> NeutralItemLocation {
+        match self.held.get(handle) {
+            Some(&(player_id, slot)) => NeutralItemLocation::Hero { player_id, slot },
+            None => NeutralItemLocation::Stash,
+        }
+    }
+
+    /// Reads items of the hero and returns changes of its neutral slots.
+    fn update(&mut self, ctx: &Context, hero: &Entity) -> Option<(i32, Vec<SlotChange>)> {
+        let player_id: i32 = try_property!(hero, "m_iPlayerID")?;
+
+        // Skip illusions and other units sharing the player id
+        let selected = ctx
+            .player_resource()
+            .and_then(|pr| pr.selected_hero(player_id as usize))
+            .ok()?;
+        if selected.index() != hero.index() {
+            return None;
+        }
+
+        let handles = hero.get_array::<EHandle>("m_hItems").ok()?;
+
+        self.held.retain(|_, (owner, _)| *owner != player_id);
+        let mut neutral = HashMap::<SlotKind, Item>::new();
+        for (slot, handle) in handles.iter().enumerate() {
+            let Ok(entity) = ctx.entities().get(handle) else {
+                continue;
+            };
+            self.held.insert(*handle, (player_id, slot));
+            if let Some(kind) = SlotKind::from_slot(slot).filter(|kind| kind.is_neutral()) {
+                if let Ok(item) = Item::new(ctx, entity) {
+                    neutral.insert(kind, item);
+                }
+            }
+        }
+
+        let changes = [SlotKind::Neutral, SlotKind::NeutralEnchantment]
+            .into_iter()
+            .filter_map(|kind| {
+                let old = self.equipped.get(&(player_id, kind));
+                let new = neutral.remove(&kind);
+                (old.map(|x| x.handle) != new.as_ref().map(|x| x.handle))
+                    .then(|| (kind, old.cloned(), new))
+            })
+            .collect();
+
+        Some((player_id, changes))
+    }
+
+    fn notify(
+        &mut self,
+        ctx: &Context,
+        player_id: i32,
+        kind: SlotKind,
+        old: Option<Item>,
+        new: Option<Item>,
+        time: f32,
+    ) -> Result<()> {
+        if let Some(old) = old {
+            self.equipped.remove(&(player_id, kind));
+            if ctx.entities().get(&old.handle).is_ok() {
+                let location = self.locate(&old.handle);
+                try_observers!(
+                    self,
+                    on_neutral_item_unequipped(ctx, player_id, &old, kind, location, time)
+                )?;
+                self.unequipped.insert(old.handle, (old, location));
+            } else {
+                try_observers!(
+                    self,
+                    on_neutral_item_consumed(ctx, player_id, &old, kind, time)
+                )?;
+            }
+        }
+
+        if let Some(new) = new {
+            self.unequipped.remove(&new.handle);
+            try_observers!(
+                self,
+                on_neutral_item_equipped(ctx, player_id, &new, kind, time)
+            )?;
+            self.equipped.insert((player_id, kind), new);
+        }
+
+        Ok(())
+    }
+
+    /// Reports unequipped items that changed location and forgets destroyed
+    /// ones.
+    fn follow_unequipped(&mut self, ctx: &Context, time: f32) -> Result<()> {
+        let handles: Vec<EHandle> = self.unequipped.keys().copied().collect();
+        for handle in handles {
+            if ctx.entities().get(&handle).is_err() {
+                self.unequipped.remove(&handle);
+                continue;
+            }
+            let location = self.locate(&handle);
+            let Some((item, old)) = self.unequipped.get_mut(&handle) else {
+                continue;
+            };
+            if *old != location {
+                *old = location;
+                let item = item.clone();
+                try_observers!(self, on_neutral_item_moved(ctx, &item, location, time))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Observer for NeutralItems {
+    fn user_messages(&mut self) -> Option<&mut dyn UserMessageObserver> {
+        Some(self)
+    }
+
+    fn on_entity(&mut self, _ctx: &Context, event: EntityEvents, entity: &Entity) -> Result<()> {
+        if (event == EntityEvents::Created || event == EntityEvents::Updated)
+            && entity.class().name().starts_with("CDOTA_Unit_Hero_")
+        {
+            self.changed_heroes.insert(entity.index());
+        }
+        Ok(())
+    }
+
+    fn on_tick_end(&mut self, ctx: &Context) -> Result<()> {
+        if self.changed_heroes.is_empty() {
+            return Ok(());
+        }
+
+        let time = ctx.game_time().unwrap_or_default();
+
+        // All heroes are read first so items passed between heroes are
+        // located correctly
+        let changes: Vec<_> = std::mem::take(&mut self.changed_heroes)
+            .into_iter()
+            .filter_map(|index| {
+                let hero = ctx.entities().get_by_index(index as usize).ok()?;
+                self.update(ctx, hero)
+            })
+            .collect();
+
+        for (player_id, changes) in changes {
+            for (kind, old, new) in changes {
+                self.notify(ctx, player_id, kind, old, new, time)?;
+            }
+        }
+
+        self.follow_unequipped(ctx, time)
+    }
+}
+
+impl UserMessageObserver for NeutralItems {
+    fn on_found_neutral_item(
+        &mut self,
+        ctx: &Context,
+        msg: &LazyMessage<CdotaUserMsgFoundNeutralItem>,
+    ) -> Result<()> {
+        let msg = msg.get()?;
+        let item_drop = NeutralItemDrop {
+            player_id: msg.player_id(),
+            item_id: msg.item_ability_id(),
+            tier: msg.item_tier(),
+            tier_count: msg.tier_item_count(),
+            time: ctx.game_time().unwrap_or_default(),
+        };
+        self.tier_times
+            .entry(item_drop.tier)
+            .or_insert(item_drop.time);
+        self.drops.push(item_drop);
+        try_observers!(self, on_neutral_item_dropped(ctx, &item_drop))
+    }
+}
+
+#[allow(unused_variables)]
+pub trait NeutralItemsObserver {
+    fn on_neutral_item_dropped(&mut self, ctx: &Context, drop: &NeutralItemDrop) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called when item appears in the neutral slot of the hero.
+    fn on_neutral_item_equipped(
+        &mut self,
+        ctx: &Context,
+        player_id: i32,
+        item: &Item,
+        slot: SlotKind,
+        time: f32,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_neutral_item_unequipped(
+        &mut self,
+        ctx: &Context,
+        player_id: i32,
+        item: &Item,
+        slot: SlotKind,
+        location: NeutralItemLocation,
+        time: f32,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_neutral_item_consumed(
+        &mut self,
+        ctx: &Context,
+        player_id: i32,
+        item: &Item,
+        slot: SlotKind,
+        time: f32,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called when unequipped item moves between the stash and inventories
+    /// of heroes.
+    fn on_neutral_item_moved(
+        &mut self,
+        ctx: &Context,
+        item: &Item,
+        location: NeutralItemLocation,
+        time: f32,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,246 @@
+use anyhow::Result;
+use hashbrown::HashMap;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use d2_stampede::prelude::*;
+use d2_stampede::proto::*;
+use d2_stampede::try_observers;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ControlPoint {
+    pub position: Option<[f32; 3]>,
+    /// Entity the control point is attached to.
+    pub entity: Option<EHandle>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Particle {
+    pub index: u32,
+    /// Hash of the particle resource name.
+    pub name_index: u64,
+    pub attach_type: i32,
+    /// Entity the particle is attached to.
+    pub entity: Option<EHandle>,
+    pub modifier_entity: Option<EHandle>,
+    pub created_tick: u32,
+    pub control_points: HashMap<i32, ControlPoint>,
+}
+
+impl Particle {
+    fn control_point(&mut self, idx: i32) -> &mut ControlPoint {
+        self.control_points.entry(idx).or_default()
+    }
+}
+
+fn vector(v: &CMsgVector) -> [f32; 3] {
+    [v.x(), v.y(), v.z()]
+}
+
+fn handle(raw: u32) -> Option<EHandle> {
+    let handle = EHandle::new(raw);
+    (!handle.is_null()).then_some(handle)
+}
+
+/// Tracks particles from `DOTA_UM_ParticleManager` messages. Control points
+/// are usually set right after the particle is created, so
+/// [`ParticlesObserver::on_particle_created`] is called at the end of the
+/// tick.
+#[derive(Default)]
+pub struct Particles {
+    observers: Vec<Rc<RefCell<dyn ParticlesObserver + 'static>>>,
+
+    particles: HashMap<u32, Particle>,
+    created: Vec<u32>,
+}
+
+impl Particles {
+    pub fn register_observer(&mut self, obs: Rc<RefCell<dyn ParticlesObserver + 'static>>) {
+        self.observers.push(obs)
+    }
+
+    /// Active particle with given index.
+    pub fn get(&self, index: u32) -> Option<&Particle> {
+        self.particles.get(&index)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Particle> {
+        self.particles.values()
+    }
+
+    /// Removes the particle, reporting its creation first if it happened in
+    /// the same tick.
+    fn destroy(&mut self, ctx: &Context, index: u32) -> Result<()> {
+        let Some(particle) = self.remove(ctx, index)? else {
+            return Ok(());
+        };
+        try_observers!(self, on_particle_destroyed(ctx, &particle))
+    }
+
+    /// Stops tracking the particle without destroying it.
+    fn release(&mut self, ctx: &Context, index: u32) -> Result<()> {
+        let Some(particle) = self.remove(ctx, index)? else {
+            return Ok(());
+        };
+        try_observers!(self, on_particle_released(ctx, &particle))
+    }
+
+    fn remove(&mut self, ctx: &Context, index: u32) -> Result<Option<Particle>> {
+        let Some(particle) = self.particles.remove(&index) else {
+            return Ok(None);
+        };
+        if let Some(idx) = self.created.iter().position(|&x| x == index) {
+            self.created.remove(idx);
+            try_observers!(self, on_particle_created(ctx, &particle))?;
+        }
+        Ok(Some(particle))
+    }
+}
+
+impl Observer for Particles {
+    fn on_dota_user_message(
+        &mut self,
+        ctx: &Context,
+        msg_type: EDotaUserMessages,
+        msg: &[u8],
+    ) -> Result<()> {
+        if msg_type != EDotaUserMessages::DotaUmParticleManager {
+            return Ok(());
+        }
+
+        let msg = CUserMsgParticleManager::decode(msg)?;
+        let index = msg.index;
+
+        match msg.r#type() {
+            ParticleMessage::GameParticleManagerEventCreate => {
+                let Some(create) = msg.create_particle.as_ref() else {
+                    return Ok(());
+                };
+                self.destroy(ctx, index)?;
+                self.particles.insert(
+                    index,
+                    Particle {
+                        index,
+                        name_index: create.particle_name_index(),
+                        attach_type: create.attach_type(),
+                        entity: handle(create.entity_handle()),
+                        modifier_entity: handle(create.entity_handle_for_modifiers()),
+                        created_tick: ctx.tick(),
+                        control_points: HashMap::default(),
+                    },
+                );
+                self.created.push(index);
+            }
+            ParticleMessage::GameParticleManagerEventUpdate => {
+                let (Some(update), Some(particle)) =
+                    (msg.update_particle.as_ref(), self.particles.get_mut(&index))
+                else {
+                    return Ok(());
+                };
+                particle.control_point(update.control_point()).position =
+                    update.position.as_ref().map(vector);
+            }
+            ParticleMessage::GameParticleManagerEventUpdateFallback => {
+                let (Some(update), Some(particle)) = (
+                    msg.update_particle_fallback.as_ref(),
+                    self.particles.get_mut(&index),
+                ) else {
+                    return Ok(());
+                };
+                particle.control_point(update.control_point()).position =
+                    update.position.as_ref().map(vector);
+            }
+            ParticleMessage::GameParticleManagerEventUpdateTransform => {
+                let (Some(update), Some(particle)) = (
+                    msg.update_particle_transform.as_ref(),
+                    self.particles.get_mut(&index),
+                ) else {
+                    return Ok(());
+                };
+                particle.control_point(update.control_point()).position =
+                    update.position.as_ref().map(vector);
+            }
+            ParticleMessage::GameParticleManagerEventUpdateEnt => {
+                let (Some(update), Some(particle)) = (
+                    msg.update_particle_ent.as_ref(),
+                    self.particles.get_mut(&index),
+                ) else {
+                    return Ok(());
+                };
+                let control_point = particle.control_point(update.control_point());
+                control_point.entity = handle(update.entity_handle());
+                if let Some(position) = update.fallback_position.as_ref() {
+                    control_point.position = Some(vector(position));
+                }
+            }
+            ParticleMessage::GameParticleManagerEventDestroy => self.destroy(ctx, index)?,
+            ParticleMessage::GameParticleManagerEventRelease => self.release(ctx, index)?,
+            ParticleMessage::GameParticleManagerEventDestroyInvolving => {
+                let Some(entity) = msg
+                    .destroy_particle_involving
+                    .as_ref()
+                    .and_then(|x| handle(x.entity_handle()))
+                else {
+                    return Ok(());
+                };
+                let indices = self
+                    .particles
+                    .values()
+                    .filter(|particle| particle.entity == Some(entity))
+                    .map(|particle| particle.index)
+                    .collect::<Vec<_>>();
+                for index in indices {
+                    self.destroy(ctx, index)?;
+                }
+            }
+            ParticleMessage::GameParticleManagerEventDestroyNamed => {
+                let Some(destroy) = msg.destroy_particle_named.as_ref() else {
+                    return Ok(());
+                };
+                let entity = handle(destroy.entity_handle());
+                let indices = self
+                    .particles
+                    .values()
+                    .filter(|particle| {
+                        particle.name_index == destroy.particle_name_index()
+                            && entity.is_none_or(|x| particle.entity == Some(x))
+                    })
+                    .map(|particle| particle.index)
+                    .collect::<Vec<_>>();
+                for index in indices {
+                    self.destroy(ctx, index)?;
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn on_tick_end(&mut self, ctx: &Context) -> Result<()> {
+        for index in std::mem::take(&mut self.created) {
+            if let Some(particle) = self.particles.get(&index) {
+                try_observers!(self, on_particle_created(ctx, particle))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[allow(unused_variables)]
+pub trait ParticlesObserver {
+    /// Called at the end of the tick the particle was created in.
+    fn on_particle_created(&mut self, ctx: &Context, particle: &Particle) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_particle_destroyed(&mut self, ctx: &Context, particle: &Particle) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called when the server releases the particle index. The effect itself
+    /// keeps playing until it finishes, no further updates are sent for it.
+    fn on_particle_released(&mut self, ctx: &Context, particle: &Particle) -> Result<()> {
+        Ok(())
+    }
+}
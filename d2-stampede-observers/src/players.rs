@@ -7,39 +7,51 @@ use d2_stampede::prelude::*;
 use d2_stampede::proto::*;
 use d2_stampede::try_observers;
 
+const HERO_CLASS_PREFIX: &str = "CDOTA_Unit_Hero_";
+
 fn class_to_combat_log(class: &str) -> HashSet<Box<str>> {
-    let name1 = "npc_dota_hero_".to_string() + &class["CDOTA_Unit_Hero_".len()..].to_lowercase();
+    let mut set = HashSet::default();
+    let Some(hero) = class.strip_prefix(HERO_CLASS_PREFIX) else {
+        return set;
+    };
+
+    let name1 = "npc_dota_hero_".to_string() + &hero.to_lowercase();
 
     let name2 = "npc_dota_hero".to_string()
-        + &class["CDOTA_Unit_Hero_".len()..]
-            .to_lowercase()
+        + &hero
             .chars()
             .map(|c| {
                 if c.is_ascii_uppercase() {
-                    format!("_{}", c.to_lowercase())
+                    format!("_{}", c.to_ascii_lowercase())
                 } else {
                     c.to_string()
                 }
             })
             .collect::<String>();
 
-    let mut set = HashSet::default();
     set.insert(name1.into_boxed_str());
     set.insert(name2.into_boxed_str());
 
     set
 }
 
-#[allow(dead_code)]
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Player {
+    /// Index in `CDOTA_PlayerResource` player data.
+    pub player_id: i32,
+    /// Steam id.
     pub id: u64,
+    pub name: Box<str>,
     pub team: i32,
     pub slot: i32,
+    /// Class name of the selected hero, empty until hero is picked.
     pub hero: Box<str>,
     pub hero_handle: usize,
 }
 
+/// Joins `userinfo` string table, `CDOTA_PlayerResource` and hero entities
+/// into [`Player`] structs. Players are refreshed at the end of every tick
+/// where any of the sources changed.
 #[derive(Default)]
 pub struct Players {
     observers: Vec<Rc<RefCell<dyn PlayersObserver + 'static>>>,
@@ -49,49 +61,125 @@ pub struct Players {
     pub steam_id_to_player: HashMap<u64, Rc<Player>>,
     pub hero_to_player: HashMap<Box<str>, Rc<Player>>,
     pub handle_to_player: HashMap<usize, Rc<Player>>,
+    names: HashMap<u64, Box<str>>,
     pre_game_tick: Option<u32>,
+    dirty: bool,
 }
 
 impl Players {
-    fn init(&mut self, ctx: &Context) -> Result<()> {
-        if let Ok(pr) = ctx.entities().get_by_class_name("CDOTA_PlayerResource") {
-            self.players = vec![];
-            self.steam_id_to_player = HashMap::default();
-            self.hero_to_player = HashMap::default();
-            let mut added = 0;
-            while added < 10 {
-                let id: u64 = property!(pr, "m_vecPlayerData.{added:04}.m_iPlayerSteamID");
-                let team: i32 = property!(pr, "m_vecPlayerData.{added:04}.m_iPlayerTeam");
-                let slot: i32 = property!(pr, "m_vecPlayerTeamData.{added:04}.m_iTeamSlot");
-                let handle: usize = property!(pr, "m_vecPlayerTeamData.{added:04}.m_hSelectedHero");
-
-                let hero_str = ctx.entities().get_by_handle(handle)?.class().name().into();
-
-                let player = Rc::new(Player {
-                    id,
-                    team,
-                    slot,
-                    hero: Box::clone(&hero_str),
-                    hero_handle: handle,
-                });
-
-                self.players.push(player.clone());
-                self.hero_to_player.insert(hero_str.clone(), player.clone());
-                self.handle_to_player.insert(handle, player.clone());
-
-                for name in class_to_combat_log(&hero_str) {
-                    self.hero_to_player.insert(name, player.clone());
-                }
+    pub fn register_observer(&mut self, obs: Rc<RefCell<dyn PlayersObserver + 'static>>) {
+        self.observers.push(obs)
+    }
 
-                self.steam_id_to_player.insert(id, player.clone());
+    pub fn iter(&self) -> impl Iterator<Item = &Player> {
+        self.players.iter().map(|player| player.as_ref())
+    }
 
-                added += 1;
+    pub fn get_by_player_id(&self, player_id: i32) -> Option<&Player> {
+        self.iter().find(|player| player.player_id == player_id)
+    }
+
+    pub fn get_by_steam_id(&self, id: u64) -> Option<&Player> {
+        self.steam_id_to_player
+            .get(&id)
+            .map(|player| player.as_ref())
+    }
+
+    /// Accepts both hero class name and combat log name, e.g.
+    /// `CDOTA_Unit_Hero_Axe` and `npc_dota_hero_axe`.
+    pub fn get_by_hero_name(&self, name: &str) -> Option<&Player> {
+        self.hero_to_player.get(name).map(|player| player.as_ref())
+    }
+
+    pub fn get_by_hero_handle(&self, handle: usize) -> Option<&Player> {
+        self.iter()
+            .find(|player| player.hero_handle & 0x3fff == handle & 0x3fff)
+    }
+
+    fn update(&mut self, ctx: &Context) -> Result<()> {
+        let Ok(pr) = ctx.entities().get_by_class_name("CDOTA_PlayerResource") else {
+            return Ok(());
+        };
+
+        let mut players = vec![];
+        let mut player_id = 0;
+        while let Some(id) = try_property!(pr, "m_vecPlayerData.{player_id:04}.m_iPlayerSteamID") {
+            let team: i32 = property!(pr, "m_vecPlayerData.{player_id:04}.m_iPlayerTeam");
+            let slot: i32 = property!(pr, "m_vecPlayerTeamData.{player_id:04}.m_iTeamSlot");
+            let hero_handle: usize =
+                property!(pr, "m_vecPlayerTeamData.{player_id:04}.m_hSelectedHero");
+
+            let hero = ctx
+                .entities()
+                .get(&EHandle::new(hero_handle as u32))
+                .ok()
+                .map(|hero| hero.class().name())
+                .filter(|class| class.starts_with(HERO_CLASS_PREFIX))
+                .map(Into::into)
+                .unwrap_or_default();
+
+            let name = self.names.get(&id).cloned().unwrap_or_else(|| {
+                try_property!(pr, "m_vecPlayerData.{player_id:04}.m_iszPlayerName")
+                    .map(|name: String| name.into_boxed_str())
+                    .unwrap_or_default()
+            });
+
+            players.push(Player {
+                player_id,
+                id,
+                name,
+                team,
+                slot,
+                hero,
+                hero_handle,
+            });
+
+            player_id += 1;
+        }
+
+        let changed = players
+            .into_iter()
+            .filter(|player| {
+                self.players
+                    .get(player.player_id as usize)
+                    .is_none_or(|old| old.as_ref() != player)
+            })
+            .collect::<Vec<_>>();
+
+        if changed.is_empty() {
+            return Ok(());
+        }
+
+        for player in changed.iter() {
+            let player = Rc::new(player.clone());
+            let idx = player.player_id as usize;
+            if idx < self.players.len() {
+                self.players[idx] = player;
+            } else {
+                self.players.push(player);
             }
+        }
 
-            self.init = true;
+        self.steam_id_to_player.clear();
+        self.hero_to_player.clear();
+        self.handle_to_player.clear();
+        for player in self.players.iter() {
+            self.steam_id_to_player.insert(player.id, player.clone());
+            if !player.hero.is_empty() {
+                self.handle_to_player
+                    .insert(player.hero_handle, player.clone());
+                self.hero_to_player
+                    .insert(player.hero.clone(), player.clone());
+                for name in class_to_combat_log(&player.hero) {
+                    self.hero_to_player.insert(name, player.clone());
+                }
+            }
+        }
 
-            try_observers!(self, on_players_init(ctx))?;
+        for player in changed.iter() {
+            try_observers!(self, on_player_updated(ctx, player))?;
         }
+
         Ok(())
     }
 }
@@ -102,8 +190,51 @@ impl Observer for Players {
             && self.pre_game_tick.is_some()
             && (self.pre_game_tick.unwrap() + 30) < ctx.tick()
         {
-            self.init(ctx)?;
+            self.update(ctx)?;
+            self.init = true;
+            try_observers!(self, on_players_init(ctx))?;
+        }
+        Ok(())
+    }
+
+    fn on_tick_end(&mut self, ctx: &Context) -> Result<()> {
+        if self.dirty {
+            self.dirty = false;
+            self.update(ctx)?;
+        }
+        Ok(())
+    }
+
+    fn on_entity(&mut self, _ctx: &Context, event: EntityEvents, entity: &Entity) -> Result<()> {
+        if (event == EntityEvents::Created || event == EntityEvents::Updated)
+            && entity.class().name() == "CDOTA_PlayerResource"
+        {
+            self.dirty = true;
+        }
+        Ok(())
+    }
+
+    fn on_string_table_update(
+        &mut self,
+        _ctx: &Context,
+        table: &StringTable,
+        modified: &[i32],
+    ) -> Result<()> {
+        if table.name() != "userinfo" {
+            return Ok(());
         }
+
+        for &idx in modified {
+            let Some(value) = table.get_entry_by_index(idx as usize)?.value() else {
+                continue;
+            };
+            let info = CMsgPlayerInfo::decode(value)?;
+            if !info.fakeplayer() {
+                self.names.insert(info.xuid(), info.name().into());
+                self.dirty = true;
+            }
+        }
+
         Ok(())
     }
 
@@ -123,4 +254,10 @@ pub trait PlayersObserver {
     fn on_players_init(&self, ctx: &Context) -> Result<()> {
         Ok(())
     }
+
+    /// Called when player appears or any of its fields changes, e.g. hero
+    /// is picked or name is received from `userinfo` table.
+    fn on_player_updated(&mut self, ctx: &Context, player: &Player) -> Result<()> {
+        Ok(())
+    }
 }
@@ -0,0 +1,143 @@
+use anyhow::Result;
+use hashbrown::HashMap;
+
+use d2_stampede::prelude::*;
+
+use crate::entity_name;
+
+/// Lowest world coordinate covered by heatmaps.
+const MAP_MIN: f32 = -8192.0;
+/// Width and height of the map in world units.
+const MAP_SIZE: f32 = 16384.0;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum TrackKind {
+    Hero,
+    ObserverWard,
+    SentryWard,
+    Courier,
+}
+
+impl TrackKind {
+    fn from_class_name(name: &str) -> Option<Self> {
+        match name {
+            "CDOTA_NPC_Observer_Ward" => Some(TrackKind::ObserverWard),
+            "CDOTA_NPC_Observer_Ward_TrueSight" => Some(TrackKind::SentryWard),
+            "CDOTA_Unit_Courier" => Some(TrackKind::Courier),
+            x if x.starts_with("CDOTA_Unit_Hero_") => Some(TrackKind::Hero),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrackPoint {
+    pub tick: u32,
+    pub x: f32,
+    pub y: f32,
+}
+
+/// Sampled positions of a single entity.
+#[derive(Debug, Clone)]
+pub struct Track {
+    pub kind: TrackKind,
+    /// Entity name, e.g. `npc_dota_hero_axe`.
+    pub name: Box<str>,
+    pub team: i32,
+    pub handle: u32,
+    pub points: Vec<TrackPoint>,
+}
+
+/// Counts points in `bins` x `bins` grid over the whole map. Rows go along
+/// `y` axis, so `grid[0][0]` is the bottom left corner of the map. Grid is
+/// empty for `0` bins.
+pub fn heatmap<'a>(points: impl Iterator<Item = &'a TrackPoint>, bins: usize) -> Vec<Vec<u32>> {
+    if bins == 0 {
+        return vec![];
+    }
+    let mut grid = vec![vec![0; bins]; bins];
+    let cell_size = MAP_SIZE / bins as f32;
+    let bin = |v: f32| (((v - MAP_MIN) / cell_size) as usize).min(bins - 1);
+    for point in points {
+        grid[bin(point.y)][bin(point.x)] += 1;
+    }
+    grid
+}
+
+/// Samples positions of heroes, wards and couriers every `interval` ticks,
+/// `30` (one second) by default.
+pub struct Positions {
+    interval: u32,
+    tracks: HashMap<u32, Track>,
+}
+
+impl Default for Positions {
+    fn default() -> Self {
+        Positions {
+            interval: 30,
+            tracks: HashMap::default(),
+        }
+    }
+}
+
+impl Positions {
+    pub fn set_interval(&mut self, ticks: u32) {
+        self.interval = ticks;
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Track> {
+        self.tracks.values()
+    }
+
+    /// Track of the entity with given handle.
+    pub fn get(&self, handle: u32) -> Option<&Track> {
+        self.tracks.get(&handle)
+    }
+
+    /// Heatmap of all tracks of given kind, see [`heatmap`].
+    pub fn heatmap(&self, kind: TrackKind, bins: usize) -> Vec<Vec<u32>> {
+        heatmap(
+            self.iter()
+                .filter(|track| track.kind == kind)
+                .flat_map(|track| track.points.iter()),
+            bins,
+        )
+    }
+}
+
+impl Observer for Positions {
+    fn interval(&self) -> Option<u32> {
+        Some(self.interval)
+    }
+
+    fn on_interval(&mut self, ctx: &Context) -> Result<()> {
+        for entity in ctx.entities().iter() {
+            let Some(kind) = TrackKind::from_class_name(entity.class().name()) else {
+                continue;
+            };
+            let Some([x, y, _]) = entity.position() else {
+                continue;
+            };
+            let point = TrackPoint {
+                tick: ctx.tick(),
+                x,
+                y,
+            };
+
+            match self.tracks.get_mut(&entity.handle()) {
+                Some(track) => track.points.push(point),
+                None => {
+                    let track = Track {
+                        kind,
+                        name: entity_name(ctx, entity).unwrap_or_default().into(),
+                        team: try_property!(entity, "m_iTeamNum").unwrap_or_default(),
+                        handle: entity.handle(),
+                        points: vec![point],
+                    };
+                    self.tracks.insert(entity.handle(), track);
+                }
+            }
+        }
+        Ok(())
+    }
+}
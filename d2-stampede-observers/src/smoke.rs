@@ -0,0 +1,248 @@
+use anyhow::Result;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use d2_stampede::prelude::*;
+use d2_stampede::try_observers;
+
+use crate::{distance, hero_position};
+
+const SMOKE_MODIFIER: &str = "modifier_smoke_of_deceit";
+/// Seconds after the smoke is broken during which kills are attributed to it.
+const GANK_WINDOW: f32 = 20.0;
+/// Smoked heroes within this distance of the victim take part in the kill.
+const CLUSTER_RADIUS: f32 = 1600.0;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum SmokeOutcome {
+    /// Smoked team got kills without losses.
+    Success,
+    /// Smoked team lost heroes without getting kills.
+    Failure,
+    /// Both teams lost heroes.
+    Trade,
+}
+
+/// Heroes that received smoke modifier in the same tick.
+#[derive(Debug, Clone)]
+pub struct SmokeGroup {
+    /// Game time of the smoke.
+    pub time: f32,
+    pub team: i32,
+    /// Combat log names of smoked heroes.
+    pub heroes: Vec<Box<str>>,
+    /// Game time when the smoke was broken or expired.
+    pub broken: Option<f32>,
+    /// Enemy heroes killed by smoked heroes.
+    pub victims: Vec<Box<str>>,
+    /// Smoked heroes killed by enemies.
+    pub losses: Vec<Box<str>>,
+    /// Smoked heroes that took part in kills or died.
+    pub participants: Vec<Box<str>>,
+    tick: u32,
+    reported: bool,
+}
+
+impl SmokeGroup {
+    pub fn outcome(&self) -> Option<SmokeOutcome> {
+        match (self.victims.is_empty(), self.losses.is_empty()) {
+            (true, true) => None,
+            (false, true) => Some(SmokeOutcome::Success),
+            (true, false) => Some(SmokeOutcome::Failure),
+            (false, false) => Some(SmokeOutcome::Trade),
+        }
+    }
+
+    fn contains(&self, hero: &str) -> bool {
+        self.heroes.iter().any(|x| x.as_ref() == hero)
+    }
+
+    fn add_participant(&mut self, hero: &str) {
+        if !self.participants.iter().any(|x| x.as_ref() == hero) {
+            self.participants.push(hero.into());
+        }
+    }
+}
+
+/// Groups heroes smoked together and follows hero kills during the smoke and
+/// [`GANK_WINDOW`] seconds after it is broken. Kill belongs to the smoke if
+/// the killer is smoked or any smoked hero is near the victim.
+#[derive(Default)]
+pub struct Smokes {
+    observers: Vec<Rc<RefCell<dyn SmokesObserver + 'static>>>,
+
+    active: Vec<SmokeGroup>,
+}
+
+impl Smokes {
+    pub fn register_observer(&mut self, obs: Rc<RefCell<dyn SmokesObserver + 'static>>) {
+        self.observers.push(obs)
+    }
+
+    /// Smoke groups that are active or still within the gank window.
+    pub fn active(&self) -> &[SmokeGroup] {
+        &self.active
+    }
+
+    fn on_hero_death(
+        &mut self,
+        ctx: &Context,
+        attacker: &str,
+        target: &str,
+        target_team: i32,
+    ) -> Result<()> {
+        let victim_position = hero_position(ctx, target);
+        for group in self.active.iter_mut() {
+            if group.contains(target) {
+                group.losses.push(target.into());
+                group.add_participant(target);
+                continue;
+            }
+            if group.team == target_team {
+                continue;
+            }
+
+            let nearby = group
+                .heroes
+                .iter()
+                .filter(|&hero| {
+                    victim_position
+                        .zip(hero_position(ctx, hero))
+                        .is_some_and(|(a, b)| distance(a, b) < CLUSTER_RADIUS)
+                })
+                .cloned()
+                .collect::<Vec<_>>();
+
+            if group.contains(attacker) || !nearby.is_empty() {
+                group.victims.push(target.into());
+                if group.contains(attacker) {
+                    group.add_participant(attacker);
+                }
+                for hero in nearby {
+                    group.add_participant(&hero);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Reports new smokes and groups with gank window over, or all groups at
+    /// the end of the replay.
+    fn finish(&mut self, ctx: &Context, all: bool) -> Result<()> {
+        for group in self.active.iter_mut().filter(|group| !group.reported) {
+            group.reported = true;
+            try_observers!(self, on_smoke_used(ctx, &group.heroes, group.time))?;
+        }
+
+        let time = ctx.game_time().unwrap_or_default();
+        let (finished, active) = std::mem::take(&mut self.active)
+            .into_iter()
+            .partition::<Vec<_>, _>(|group| {
+                all || group.broken.is_some_and(|x| x + GANK_WINDOW < time)
+            });
+        self.active = active;
+
+        for group in finished {
+            if let Some(outcome) = group.outcome() {
+                try_observers!(
+                    self,
+                    on_smoke_gank(ctx, &group.participants, &group.victims, outcome)
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Observer for Smokes {
+    fn on_combat_log(&mut self, ctx: &Context, combat_log: &CombatLog) -> Result<()> {
+        let Ok(entry) = combat_log.entry() else {
+            return Ok(());
+        };
+
+        match entry {
+            CombatLogEntry::ModifierAdd {
+                target: Some(target),
+                modifier: Some(SMOKE_MODIFIER),
+                target_hero: true,
+                ..
+            } => {
+                let team = combat_log.target_team().unwrap_or_default() as i32;
+                match self
+                    .active
+                    .iter_mut()
+                    .find(|group| group.tick == ctx.tick() && group.team == team)
+                {
+                    Some(group) => group.heroes.push(target.into()),
+                    None => self.active.push(SmokeGroup {
+                        time: ctx.game_time().unwrap_or_default(),
+                        team,
+                        heroes: vec![target.into()],
+                        broken: None,
+                        victims: vec![],
+                        losses: vec![],
+                        participants: vec![],
+                        tick: ctx.tick(),
+                        reported: false,
+                    }),
+                }
+            }
+            CombatLogEntry::ModifierRemove {
+                target: Some(target),
+                modifier: Some(SMOKE_MODIFIER),
+                ..
+            } => {
+                for group in self.active.iter_mut() {
+                    if group.broken.is_none() && group.contains(target) {
+                        group.broken = Some(ctx.game_time().unwrap_or_default());
+                    }
+                }
+            }
+            CombatLogEntry::Death {
+                attacker,
+                target,
+                target_hero: true,
+                target_illusion: false,
+                ..
+            } => {
+                let target_team = combat_log.target_team().unwrap_or_default() as i32;
+                let (attacker, target) = (attacker.unwrap_or_default(), target.unwrap_or_default());
+                self.on_hero_death(ctx, attacker, target, target_team)?;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn on_tick_end(&mut self, ctx: &Context) -> Result<()> {
+        if self.active.is_empty() {
+            return Ok(());
+        }
+        self.finish(ctx, false)
+    }
+
+    fn epilogue(&mut self, ctx: &Context) -> Result<()> {
+        self.finish(ctx, true)
+    }
+}
+
+#[allow(unused_variables)]
+pub trait SmokesObserver {
+    /// Called when heroes are smoked. `heroes` are combat log names.
+    fn on_smoke_used(&mut self, ctx: &Context, heroes: &[Box<str>], time: f32) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called after the gank window of the smoke is over if any hero died.
+    fn on_smoke_gank(
+        &mut self,
+        ctx: &Context,
+        participants: &[Box<str>],
+        victims: &[Box<str>],
+        outcome: SmokeOutcome,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
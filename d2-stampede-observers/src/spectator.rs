@@ -0,0 +1,193 @@
+use anyhow::Result;
+use hashbrown::HashMap;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use d2_stampede::prelude::*;
+use d2_stampede::proto::{
+    CdotaUserMsgMoveCameraToUnit, CdotaUserMsgSpectatorPlayerClick,
+    CdotaUserMsgSpectatorPlayerUnitOrders,
+};
+use d2_stampede::try_observers;
+
+/// `m_iTeamNum` of spectators and casters.
+pub const TEAM_SPECTATOR: i32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Camera {
+    /// Index of `CDOTAPlayerController` entity.
+    pub controller: u32,
+    pub player_id: i32,
+    pub team: i32,
+    /// Position of the player's pawn, which follows the camera.
+    pub position: [f32; 3],
+}
+
+impl Camera {
+    /// True for spectator slots, including casters in broadcast replays.
+    pub fn is_spectator(&self) -> bool {
+        self.team == TEAM_SPECTATOR
+    }
+}
+
+/// Follows cameras of players and spectators by `CDOTAPlayerController`
+/// pawns, and director actions from `DOTA_UM_MoveCameraToUnit`,
+/// `DOTA_UM_SpectatorPlayerClick` and `DOTA_UM_SpectatorPlayerUnitOrders`
+/// messages.
+#[derive(Default)]
+pub struct Spectator {
+    observers: Vec<Rc<RefCell<dyn SpectatorObserver + 'static>>>,
+
+    cameras: HashMap<u32, Camera>,
+    followed: Option<EHandle>,
+    pawns_changed: bool,
+}
+
+impl Spectator {
+    pub fn register_observer(&mut self, obs: Rc<RefCell<dyn SpectatorObserver + 'static>>) {
+        self.observers.push(obs)
+    }
+
+    /// Camera of the controller with given entity index.
+    pub fn camera(&self, controller: u32) -> Option<&Camera> {
+        self.cameras.get(&controller)
+    }
+
+    pub fn cameras(&self) -> impl Iterator<Item = &Camera> {
+        self.cameras.values()
+    }
+
+    /// Cameras of spectator slots.
+    pub fn spectators(&self) -> impl Iterator<Item = &Camera> {
+        self.cameras().filter(|camera| camera.is_spectator())
+    }
+
+    /// Unit the camera was last moved to.
+    pub fn followed_unit(&self) -> Option<EHandle> {
+        self.followed
+    }
+
+    fn update_cameras(&mut self, ctx: &Context) -> Result<()> {
+        let mut cameras = HashMap::default();
+        for controller in ctx
+            .entities()
+            .query()
+            .class_name("CDOTAPlayerController")
+            .iter()
+        {
+            let Some(pawn): Option<EHandle> = try_property!(controller, "m_hPawn") else {
+                continue;
+            };
+            let Some(position) = ctx
+                .entities()
+                .get(&pawn)
+                .ok()
+                .and_then(|pawn| pawn.position())
+            else {
+                continue;
+            };
+            let camera = Camera {
+                controller: controller.index(),
+                player_id: try_property!(controller, "m_nPlayerID").unwrap_or(-1),
+                team: try_property!(controller, "m_iTeamNum").unwrap_or_default(),
+                position,
+            };
+            if self
+                .cameras
+                .get(&camera.controller)
+                .is_none_or(|old| old.position != camera.position)
+            {
+                try_observers!(self, on_camera_moved(ctx, &camera))?;
+            }
+            cameras.insert(camera.controller, camera);
+        }
+        self.cameras = cameras;
+        Ok(())
+    }
+}
+
+impl Observer for Spectator {
+    fn user_messages(&mut self) -> Option<&mut dyn UserMessageObserver> {
+        Some(self)
+    }
+
+    fn on_entity(&mut self, _ctx: &Context, _event: EntityEvents, entity: &Entity) -> Result<()> {
+        let class = entity.class().name();
+        if class == "CDOTAPlayerPawn" || class == "CDOTAPlayerController" {
+            self.pawns_changed = true;
+        }
+        Ok(())
+    }
+
+    fn on_tick_end(&mut self, ctx: &Context) -> Result<()> {
+        if std::mem::take(&mut self.pawns_changed) {
+            self.update_cameras(ctx)?;
+        }
+        Ok(())
+    }
+}
+
+impl UserMessageObserver for Spectator {
+    fn on_move_camera_to_unit(
+        &mut self,
+        ctx: &Context,
+        msg: &LazyMessage<CdotaUserMsgMoveCameraToUnit>,
+    ) -> Result<()> {
+        let unit = EHandle::new(msg.get()?.unit_ehandle());
+        if unit.is_null() {
+            return Ok(());
+        }
+        self.followed = Some(unit);
+        try_observers!(self, on_follow_unit(ctx, unit))
+    }
+
+    fn on_spectator_player_click(
+        &mut self,
+        ctx: &Context,
+        msg: &LazyMessage<CdotaUserMsgSpectatorPlayerClick>,
+    ) -> Result<()> {
+        let msg = msg.get()?;
+        try_observers!(
+            self,
+            on_spectator_order(ctx, msg.entindex, msg.order_type(), msg.target_index())
+        )
+    }
+
+    fn on_spectator_player_unit_orders(
+        &mut self,
+        ctx: &Context,
+        msg: &LazyMessage<CdotaUserMsgSpectatorPlayerUnitOrders>,
+    ) -> Result<()> {
+        let msg = msg.get()?;
+        try_observers!(
+            self,
+            on_spectator_order(ctx, msg.entindex(), msg.order_type(), msg.target_index())
+        )
+    }
+}
+
+#[allow(unused_variables)]
+pub trait SpectatorObserver {
+    /// Called at the end of the tick when the pawn of the controller moved.
+    fn on_camera_moved(&mut self, ctx: &Context, camera: &Camera) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called for `DOTA_UM_MoveCameraToUnit`, e.g. when the director switches
+    /// to another hero.
+    fn on_follow_unit(&mut self, ctx: &Context, unit: EHandle) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called for unit orders issued by spectated players. `entindex` is the
+    /// unit the order was issued for.
+    fn on_spectator_order(
+        &mut self,
+        ctx: &Context,
+        entindex: i32,
+        order_type: i32,
+        target_index: i32,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,327 @@
+use anyhow::Result;
+use hashbrown::HashMap;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use d2_stampede::prelude::*;
+use d2_stampede::proto::DotaGameState;
+use d2_stampede::try_observers;
+
+use crate::{distance, hero_position};
+
+/// Seconds without hero deaths after which the fight is over.
+const COOLDOWN: f32 = 15.0;
+/// Fights with fewer deaths are not reported.
+const MIN_DEATHS: usize = 3;
+/// Deaths further than this from the fight centroid start a separate fight.
+const FIGHT_RADIUS: f32 = 4000.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TeamfightDeath {
+    pub time: f32,
+    pub victim: Box<str>,
+    pub killer: Box<str>,
+    pub position: Option<[f32; 3]>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Participant {
+    pub team: i32,
+    pub damage: u32,
+    pub healing: u32,
+    pub deaths: u32,
+    pub gold_delta: i32,
+    pub xp_delta: i32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Teamfight {
+    pub start_tick: u32,
+    pub end_tick: u32,
+    /// Combat log time of the first hero damage included in the fight.
+    pub start_time: f32,
+    /// Combat log time of the last death.
+    pub end_time: f32,
+    pub deaths: Vec<TeamfightDeath>,
+    /// Participants by hero combat log name.
+    pub participants: HashMap<Box<str>, Participant>,
+}
+
+impl Teamfight {
+    /// Average position of the deaths.
+    pub fn centroid(&self) -> Option<[f32; 3]> {
+        let positions = self
+            .deaths
+            .iter()
+            .filter_map(|death| death.position)
+            .collect::<Vec<_>>();
+        if positions.is_empty() {
+            return None;
+        }
+        let n = positions.len() as f32;
+        Some(positions.iter().fold([0.0; 3], |acc, x| {
+            [acc[0] + x[0] / n, acc[1] + x[1] / n, acc[2] + x[2] / n]
+        }))
+    }
+
+    /// Gold earned by the heroes of given team during the fight.
+    pub fn gold_delta(&self, team: i32) -> i32 {
+        self.participants
+            .values()
+            .filter(|participant| participant.team == team)
+            .map(|participant| participant.gold_delta)
+            .sum()
+    }
+
+    /// Experience earned by the heroes of given team during the fight.
+    pub fn xp_delta(&self, team: i32) -> i32 {
+        self.participants
+            .values()
+            .filter(|participant| participant.team == team)
+            .map(|participant| participant.xp_delta)
+            .sum()
+    }
+
+    fn participant(&mut self, hero: &str, team: i32) -> &mut Participant {
+        self.participants
+            .entry(hero.into())
+            .or_insert_with(|| Participant {
+                team,
+                ..Default::default()
+            })
+    }
+
+    fn is_participant(&self, hero: &str) -> bool {
+        self.participants.contains_key(hero)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct HeroDamage {
+    time: f32,
+    attacker: Box<str>,
+    attacker_team: i32,
+    target: Box<str>,
+    target_team: i32,
+    damage: u32,
+}
+
+/// Detects teamfights from combat log in the way similar to OpenDota. Fight
+/// starts with a hero death and lasts while heroes keep dying within
+/// [`COOLDOWN`] seconds near the fight. Hero damage from the cooldown before
+/// the first death is included, heroes that dealt or received damage
+/// involving other participants join the fight. Fights that are still going
+/// on are reported when the game is over or the replay ends.
+#[derive(Default)]
+pub struct Teamfights {
+    observers: Vec<Rc<RefCell<dyn TeamfightsObserver + 'static>>>,
+
+    active: Vec<Teamfight>,
+    time: f32,
+    recent_damage: VecDeque<HeroDamage>,
+    teamfights: Vec<Teamfight>,
+}
+
+impl Teamfights {
+    pub fn register_observer(&mut self, obs: Rc<RefCell<dyn TeamfightsObserver + 'static>>) {
+        self.observers.push(obs)
+    }
+
+    /// Finished teamfights.
+    pub fn teamfights(&self) -> &[Teamfight] {
+        &self.teamfights
+    }
+
+    fn on_death(&mut self, ctx: &Context, combat_log: &CombatLog, death: TeamfightDeath) {
+        let target_team = combat_log.target_team().unwrap_or_default() as i32;
+        let attacker_team = combat_log.attacker_team().unwrap_or_default() as i32;
+        let attacker_hero = combat_log.attacker_hero().unwrap_or_default();
+
+        let idx = self.active.iter().position(|fight| {
+            fight
+                .centroid()
+                .zip(death.position)
+                .is_none_or(|(a, b)| distance(a, b) < FIGHT_RADIUS)
+        });
+        let fight = match idx {
+            Some(idx) => &mut self.active[idx],
+            None => {
+                self.active.push(Teamfight {
+                    start_tick: ctx.tick(),
+                    start_time: death.time,
+                    ..Default::default()
+                });
+                self.active.last_mut().unwrap()
+            }
+        };
+
+        fight.participant(&death.victim, target_team).deaths += 1;
+        if attacker_hero {
+            fight.participant(&death.killer, attacker_team);
+        }
+        fight.end_tick = ctx.tick();
+        fight.end_time = death.time;
+
+        if idx.is_none() {
+            for damage in self.recent_damage.iter() {
+                if fight.is_participant(&damage.attacker) || fight.is_participant(&damage.target) {
+                    fight.start_time = fight.start_time.min(damage.time);
+                    fight
+                        .participant(&damage.attacker, damage.attacker_team)
+                        .damage += damage.damage;
+                    fight.participant(&damage.target, damage.target_team);
+                }
+            }
+        }
+
+        fight.deaths.push(death);
+    }
+
+    /// Reports fights with no deaths for [`COOLDOWN`] seconds, or all active
+    /// fights when the game is over.
+    fn finish(&mut self, ctx: &Context, game_over: bool) -> Result<()> {
+        let time = self.time;
+        let (finished, active) = std::mem::take(&mut self.active)
+            .into_iter()
+            .partition::<Vec<_>, _>(|fight| game_over || fight.end_time + COOLDOWN < time);
+        self.active = active;
+
+        for fight in finished {
+            if fight.deaths.len() >= MIN_DEATHS {
+                try_observers!(self, on_teamfight_ended(ctx, &fight))?;
+                self.teamfights.push(fight);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn on_damage(&mut self, damage: HeroDamage) {
+        for fight in self.active.iter_mut() {
+            if fight.is_participant(&damage.attacker) || fight.is_participant(&damage.target) {
+                fight
+                    .participant(&damage.attacker, damage.attacker_team)
+                    .damage += damage.damage;
+                fight.participant(&damage.target, damage.target_team);
+            }
+        }
+
+        while self
+            .recent_damage
+            .front()
+            .is_some_and(|x| x.time + COOLDOWN < damage.time)
+        {
+            self.recent_damage.pop_front();
+        }
+        self.recent_damage.push_back(damage);
+    }
+}
+
+impl Observer for Teamfights {
+    fn on_combat_log(&mut self, ctx: &Context, combat_log: &CombatLog) -> Result<()> {
+        let Ok(entry) = combat_log.entry() else {
+            return Ok(());
+        };
+        self.time = self.time.max(entry.game_time());
+
+        match entry {
+            CombatLogEntry::Death {
+                time,
+                attacker,
+                target,
+                target_hero: true,
+                target_illusion: false,
+                ..
+            } => {
+                let target = target.unwrap_or_default();
+                let death = TeamfightDeath {
+                    time,
+                    victim: target.into(),
+                    killer: attacker.unwrap_or_default().into(),
+                    position: hero_position(ctx, target),
+                };
+                self.on_death(ctx, combat_log, death);
+            }
+            CombatLogEntry::Damage {
+                time,
+                attacker,
+                target,
+                damage,
+                target_hero: true,
+                target_illusion: false,
+                ..
+            } if combat_log.attacker_hero().unwrap_or_default()
+                && !combat_log.attacker_illusion().unwrap_or_default() =>
+            {
+                self.on_damage(HeroDamage {
+                    time,
+                    attacker: attacker.unwrap_or_default().into(),
+                    attacker_team: combat_log.attacker_team().unwrap_or_default() as i32,
+                    target: target.unwrap_or_default().into(),
+                    target_team: combat_log.target_team().unwrap_or_default() as i32,
+                    damage,
+                });
+            }
+            CombatLogEntry::Heal {
+                attacker: Some(attacker),
+                amount,
+                target_hero: true,
+                ..
+            } => {
+                for fight in self.active.iter_mut() {
+                    if let Some(participant) = fight.participants.get_mut(attacker) {
+                        participant.healing += amount;
+                    }
+                }
+            }
+            CombatLogEntry::GoldChange {
+                target: Some(target),
+                amount,
+                ..
+            } => {
+                for fight in self.active.iter_mut() {
+                    if let Some(participant) = fight.participants.get_mut(target) {
+                        participant.gold_delta += amount;
+                    }
+                }
+            }
+            CombatLogEntry::XpGain {
+                target: Some(target),
+                amount,
+                ..
+            } => {
+                for fight in self.active.iter_mut() {
+                    if let Some(participant) = fight.participants.get_mut(target) {
+                        participant.xp_delta += amount as i32;
+                    }
+                }
+            }
+            CombatLogEntry::GameState {
+                state: DotaGameState::DotaGamerulesStatePostGame,
+                ..
+            } => self.finish(ctx, true)?,
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn on_tick_end(&mut self, ctx: &Context) -> Result<()> {
+        if !self.active.is_empty() {
+            self.finish(ctx, false)?;
+        }
+        Ok(())
+    }
+
+    fn epilogue(&mut self, ctx: &Context) -> Result<()> {
+        self.finish(ctx, true)
+    }
+}
+
+#[allow(unused_variables)]
+pub trait TeamfightsObserver {
+    fn on_teamfight_ended(&mut self, ctx: &Context, teamfight: &Teamfight) -> Result<()> {
+        Ok(())
+    }
+}
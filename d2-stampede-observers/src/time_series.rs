@@ -0,0 +1,155 @@
+use anyhow::Result;
+use hashbrown::HashMap;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use d2_stampede::prelude::*;
+use d2_stampede::try_observers;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Sample {
+    pub tick: u32,
+    /// Game time in seconds.
+    pub time: f32,
+    /// Total earned gold.
+    pub gold: i32,
+    /// Total earned experience.
+    pub xp: i32,
+    pub last_hits: i32,
+    pub denies: i32,
+    pub net_worth: i32,
+}
+
+/// Samples player stats from `CDOTA_DataRadiant` and `CDOTA_DataDire` every
+/// `interval` seconds of game time, starting from the horn.
+pub struct TimeSeries {
+    observers: Vec<Rc<RefCell<dyn TimeSeriesObserver + 'static>>>,
+
+    interval: f32,
+    next_sample: f32,
+    teams: HashMap<i32, i32>,
+    series: HashMap<i32, Vec<Sample>>,
+}
+
+impl Default for TimeSeries {
+    fn default() -> Self {
+        TimeSeries {
+            observers: vec![],
+            interval: 1.0,
+            next_sample: 0.0,
+            teams: HashMap::default(),
+            series: HashMap::default(),
+        }
+    }
+}
+
+impl TimeSeries {
+    pub fn register_observer(&mut self, obs: Rc<RefCell<dyn TimeSeriesObserver + 'static>>) {
+        self.observers.push(obs)
+    }
+
+    /// Sampling interval in seconds of game time, `1.0` by default. Intervals
+    /// shorter than one tick are clamped to it.
+    pub fn set_interval(&mut self, interval: f32) {
+        self.interval = interval.max(1.0 / 30.0);
+    }
+
+    /// Samples of the player with given id.
+    pub fn get(&self, player_id: i32) -> &[Sample] {
+        self.series
+            .get(&player_id)
+            .map(|x| x.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Player ids with their samples.
+    pub fn iter(&self) -> impl Iterator<Item = (i32, &[Sample])> {
+        self.series
+            .iter()
+            .map(|(&player_id, samples)| (player_id, samples.as_slice()))
+    }
+
+    /// Radiant minus Dire total earned gold and experience for every sample
+    /// tick, as `(time, gold, xp)`. Players without a sample at some tick
+    /// don't contribute to it.
+    pub fn advantage(&self) -> Vec<(f32, i32, i32)> {
+        let mut advantage: BTreeMap<u32, (f32, i32, i32)> = BTreeMap::new();
+        for (player_id, samples) in self.series.iter() {
+            let sign = match self.teams.get(player_id) {
+                Some(2) => 1,
+                Some(3) => -1,
+                _ => continue,
+            };
+            for sample in samples.iter() {
+                let entry = advantage.entry(sample.tick).or_insert((sample.time, 0, 0));
+                entry.1 += sign * sample.gold;
+                entry.2 += sign * sample.xp;
+            }
+        }
+        advantage.into_values().collect()
+    }
+
+    fn sample(&mut self, ctx: &Context, time: f32) -> Result<()> {
+        let pr = ctx.entities().get_by_class_name("CDOTA_PlayerResource")?;
+
+        let mut player_id = 0;
+        while let Some(team) = try_property!(pr, "m_vecPlayerData.{player_id:04}.m_iPlayerTeam") {
+            let class = match team {
+                2 => "CDOTA_DataRadiant",
+                3 => "CDOTA_DataDire",
+                _ => {
+                    player_id += 1;
+                    continue;
+                }
+            };
+            let data = ctx.entities().get_by_class_name(class)?;
+            let slot: i32 = property!(pr, "m_vecPlayerTeamData.{player_id:04}.m_iTeamSlot");
+
+            let sample = Sample {
+                tick: ctx.tick(),
+                time,
+                gold: property!(data, "m_vecDataTeam.{slot:04}.m_iTotalEarnedGold"),
+                xp: property!(data, "m_vecDataTeam.{slot:04}.m_iTotalEarnedXP"),
+                last_hits: property!(data, "m_vecDataTeam.{slot:04}.m_iLastHitCount"),
+                denies: property!(data, "m_vecDataTeam.{slot:04}.m_iDenyCount"),
+                net_worth: property!(data, "m_vecDataTeam.{slot:04}.m_iNetWorth"),
+            };
+
+            try_observers!(self, on_sample(ctx, player_id, &sample))?;
+            self.teams.insert(player_id, team);
+            self.series.entry(player_id).or_default().push(sample);
+
+            player_id += 1;
+        }
+
+        Ok(())
+    }
+}
+
+impl Observer for TimeSeries {
+    fn on_tick_end(&mut self, ctx: &Context) -> Result<()> {
+        let Ok(game_rules) = ctx.game_rules() else {
+            return Ok(());
+        };
+        if game_rules.game_start_time()? == 0.0 {
+            return Ok(());
+        }
+
+        let time = game_rules.game_time()?;
+        if time >= self.next_sample {
+            self.sample(ctx, time)?;
+            self.next_sample +=
+                self.interval * ((time - self.next_sample) / self.interval + 1.0).floor();
+        }
+
+        Ok(())
+    }
+}
+
+#[allow(unused_variables)]
+pub trait TimeSeriesObserver {
+    fn on_sample(&mut self, ctx: &Context, player_id: i32, sample: &Sample) -> Result<()> {
+        Ok(())
+    }
+}
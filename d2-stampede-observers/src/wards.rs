@@ -38,6 +38,33 @@ impl WardClass {
     }
 }
 
+/// Ward state at the moment of the event.
+#[derive(Debug, Clone)]
+pub struct Ward {
+    pub class: WardClass,
+    pub index: u32,
+    pub handle: u32,
+    pub team: i32,
+    /// Hero that placed the ward.
+    pub owner: Option<EHandle>,
+    pub position: Option<[f32; 3]>,
+    pub game_time: Option<f32>,
+}
+
+impl Ward {
+    fn new(ctx: &Context, class: WardClass, entity: &Entity) -> Self {
+        Ward {
+            class,
+            index: entity.index(),
+            handle: entity.handle(),
+            team: try_property!(entity, "m_iTeamNum").unwrap_or_default(),
+            owner: try_property!(entity, "m_hOwnerEntity"),
+            position: entity.position(),
+            game_time: ctx.game_time().ok(),
+        }
+    }
+}
+
 #[derive(Debug)]
 struct PendingEvent {
     entity_idx: u32,
@@ -80,23 +107,19 @@ impl Observer for Wards {
             let old_state = *self.current_life_state.get(&ev.entity_idx).unwrap_or(&2);
             let new_state = ev.life_state;
 
-            let ward_class = WardClass::from_class_name(
-                ctx.entities()
-                    .get_by_index(ev.entity_idx as usize)?
-                    .class()
-                    .name(),
-            )?;
+            let entity = ctx.entities().get_by_index(ev.entity_idx as usize)?;
+            let ward_class = WardClass::from_class_name(entity.class().name())?;
+            let ward = Ward::new(ctx, ward_class, entity);
 
             let event = |event: WardEvent| -> d2_stampede::Result<()> {
-                try_observers!(
-                    self,
-                    on_ward(
-                        ctx,
-                        ward_class,
-                        event.clone(),
-                        ctx.entities().get_by_index(ev.entity_idx as usize)?
-                    )
-                )
+                try_observers!(self, on_ward(ctx, ward_class, event.clone(), entity))?;
+                match &event {
+                    WardEvent::Placed => try_observers!(self, on_ward_placed(ctx, &ward)),
+                    WardEvent::Killed(killer) => {
+                        try_observers!(self, on_ward_killed(ctx, &ward, killer))
+                    }
+                    WardEvent::Expired => try_observers!(self, on_ward_expired(ctx, &ward)),
+                }
             };
 
             if old_state != new_state {
@@ -195,4 +218,22 @@ pub trait WardsObserver {
     ) -> d2_stampede::Result<()> {
         Ok(())
     }
+
+    fn on_ward_placed(&mut self, ctx: &Context, ward: &Ward) -> d2_stampede::Result<()> {
+        Ok(())
+    }
+
+    fn on_ward_expired(&mut self, ctx: &Context, ward: &Ward) -> d2_stampede::Result<()> {
+        Ok(())
+    }
+
+    /// `killer` is the combat log name of the unit that destroyed the ward.
+    fn on_ward_killed(
+        &mut self,
+        ctx: &Context,
+        ward: &Ward,
+        killer: &str,
+    ) -> d2_stampede::Result<()> {
+        Ok(())
+    }
 }
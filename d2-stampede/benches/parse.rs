@@ -0,0 +1,233 @@
+//! Benchmarks parsing a real replay. Path to the replay is taken from
+//! `D2_STAMPEDE_REPLAY`, without it only `reader` group is run:
+//!
+//! ```sh
+//! D2_STAMPEDE_REPLAY=replay.dem cargo bench -p d2-stampede
+//! ```
+
+use criterion::Criterion;
+use d2_stampede::prelude::*;
+use d2_stampede::SchemaCache;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[allow(dead_code)]
+#[path = "reader/bitter.rs"]
+mod bitter;
+#[allow(dead_code)]
+#[path = "../src/reader.rs"]
+mod buffered;
+
+/// Reads health of every hero on every update, a job that only needs a
+/// handful of properties.
+fn hero_health(parser: &mut Parser) {
+    parser.on_entity(
+        |entity| entity.class().name().starts_with("CDOTA_Unit_Hero_"),
+        |_, _, entity| {
+            let _: Option<i32> = try_property!(entity, "m_iHealth");
+            Ok(())
+        },
+    );
+}
+
+fn entity_decoding(c: &mut Criterion, replay: &[u8]) {
+    let mut group = c.benchmark_group("entity_decoding");
+    group.sample_size(10);
+
+    group.bench_function("all", |b| {
+        b.iter(|| {
+            let mut parser = Parser::new(replay).unwrap();
+            hero_health(&mut parser);
+            parser.run_to_end().unwrap();
+        })
+    });
+
+    group.bench_function("lazy", |b| {
+        b.iter(|| {
+            let mut parser = Parser::new(replay).unwrap();
+            parser.enable_lazy_decoding();
+            hero_health(&mut parser);
+            parser.run_to_end().unwrap();
+        })
+    });
+
+    // Other classes are skipped without storing anything.
+    group.bench_function("skip", |b| {
+        b.iter(|| {
+            let mut parser = Parser::new(replay).unwrap();
+            parser.decode_only_classes(&["CDOTAGamerulesProxy", "CDOTA_PlayerResource"]);
+            parser.run_to_end().unwrap();
+        })
+    });
+
+    group.finish();
+}
+
+fn snapshots(c: &mut Criterion, replay: &[u8]) {
+    let mut group = c.benchmark_group("snapshots");
+    group.sample_size(10);
+
+    let mut parser = Parser::new(replay).unwrap();
+    parser.run_to_end().unwrap();
+    let entities = parser.context.entities();
+
+    // Clones the entity list and bumps reference counts of the states.
+    group.bench_function("snapshot", |b| b.iter(|| entities.snapshot()));
+
+    // Whole parse keeping a snapshot of every minute, compare with
+    // entity_decoding/all.
+    group.bench_function("every_minute", |b| {
+        b.iter(|| {
+            let snapshots = Rc::new(RefCell::new(vec![]));
+            let mut parser = Parser::new(replay).unwrap();
+            let inner = snapshots.clone();
+            parser.on_interval(1800, move |ctx| {
+                inner.borrow_mut().push(ctx.entities().snapshot());
+                Ok(())
+            });
+            parser.run_to_end().unwrap();
+            snapshots
+        })
+    });
+
+    group.finish();
+}
+
+/// Same replay parsed several times, like a batch of replays from one game
+/// build.
+fn schema_cache(c: &mut Criterion, replay: &[u8]) {
+    const PARSES: usize = 5;
+
+    let mut group = c.benchmark_group("schema_cache");
+    group.sample_size(10);
+
+    group.bench_function("none", |b| {
+        b.iter(|| {
+            for _ in 0..PARSES {
+                Parser::new(replay).unwrap().run_to_end().unwrap();
+            }
+        })
+    });
+
+    group.bench_function("shared", |b| {
+        b.iter(|| {
+            let cache = SchemaCache::new();
+            for _ in 0..PARSES {
+                let mut parser = Parser::new(replay).unwrap();
+                parser.set_schema_cache(cache.clone());
+                parser.run_to_end().unwrap();
+            }
+        })
+    });
+
+    group.finish();
+}
+
+/// Baseline decoded on every entity creation compared with the state decoded
+/// once per class and shared.
+fn baselines(c: &mut Criterion, replay: &[u8]) {
+    let mut group = c.benchmark_group("baselines");
+    group.sample_size(10);
+
+    group.bench_function("cold", |b| {
+        b.iter(|| {
+            let mut parser = Parser::new(replay).unwrap();
+            parser.disable_baseline_cache();
+            parser.run_to_end().unwrap();
+        })
+    });
+
+    group.bench_function("cached", |b| {
+        b.iter(|| Parser::new(replay).unwrap().run_to_end().unwrap())
+    });
+
+    group.finish();
+}
+
+/// Packets decompressed and decoded on a separate thread compared with
+/// reading them on the parsing thread.
+#[cfg(feature = "parallel")]
+fn parallel_reading(c: &mut Criterion, replay: &[u8]) {
+    let mut group = c.benchmark_group("parallel_reading");
+    group.sample_size(10);
+
+    group.bench_function("sequential", |b| {
+        b.iter(|| {
+            let mut parser = Parser::new(replay).unwrap();
+            parser.disable_parallel_reading();
+            parser.run_to_end().unwrap();
+        })
+    });
+
+    group.bench_function("parallel", |b| {
+        b.iter(|| Parser::new(replay).unwrap().run_to_end().unwrap())
+    });
+
+    group.finish();
+}
+
+/// Random bits, deterministic so runs are comparable.
+fn random_bytes(len: usize) -> Vec<u8> {
+    let mut state = 0x9e37_79b9_7f4a_7c15u64;
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as u8
+        })
+        .collect()
+}
+
+/// Current bit reader compared with the `bitter` one it replaced. Number
+/// of reads is fixed by the widest value, so neither reader reaches the end
+/// of the data.
+fn reader(c: &mut Criterion) {
+    let data = random_bytes(1 << 16);
+
+    let mut group = c.benchmark_group("reader");
+
+    macro_rules! compare {
+        ($name:literal, $max_bits:expr, |$r:ident| $read:expr) => {
+            let reads = (data.len() - 64) * 8 / $max_bits;
+            group.bench_function(concat!($name, "/bitter"), |b| {
+                b.iter(|| {
+                    let mut $r = bitter::Reader::new(&data);
+                    (0..reads).fold(0u64, |acc, _| acc.wrapping_add($read as u64))
+                })
+            });
+            group.bench_function(concat!($name, "/buffered"), |b| {
+                b.iter(|| {
+                    let mut $r = buffered::Reader::new(&data);
+                    (0..reads).fold(0u64, |acc, _| acc.wrapping_add($read as u64))
+                })
+            });
+        };
+    }
+
+    compare!("read_ubit_var", 34, |r| r.read_ubit_var());
+    compare!("read_var_u32", 40, |r| r.read_var_u32());
+    compare!("read_bits", 17, |r| r.read_bits(17));
+
+    group.finish();
+}
+
+fn main() {
+    let mut c = Criterion::default().configure_from_args();
+    reader(&mut c);
+
+    let Ok(path) = std::env::var("D2_STAMPEDE_REPLAY") else {
+        eprintln!("D2_STAMPEDE_REPLAY is not set, skipping replay benchmarks");
+        c.final_summary();
+        return;
+    };
+    let replay = std::fs::read(path).unwrap();
+
+    entity_decoding(&mut c, &replay);
+    snapshots(&mut c, &replay);
+    schema_cache(&mut c, &replay);
+    baselines(&mut c, &replay);
+    #[cfg(feature = "parallel")]
+    parallel_reading(&mut c, &replay);
+    c.final_summary();
+}
@@ -1,14 +1,15 @@
+use crate::field::Encoder;
 use crate::serializer::Serializer;
 use anyhow::{anyhow, Context, Result};
 use hashbrown::HashMap;
 use prettytable::{row, Table};
 use std::fmt::{Display, Formatter};
-use std::rc::Rc;
+use std::sync::Arc;
 
 /// Container of
 pub struct Classes {
-    pub(crate) classes_vec: Vec<Rc<Class>>,
-    pub(crate) classes_by_name: HashMap<Box<str>, Rc<Class>>,
+    pub(crate) classes_vec: Vec<Arc<Class>>,
+    pub(crate) classes_by_name: HashMap<Box<str>, Arc<Class>>,
     pub(crate) class_id_size: Option<u32>,
 }
 
@@ -21,7 +22,7 @@ impl Classes {
         }
     }
 
-    pub(crate) fn get_by_id_rc(&self, id: usize) -> Result<&Rc<Class>> {
+    pub(crate) fn get_by_id_rc(&self, id: usize) -> Result<&Arc<Class>> {
         Ok(&self.classes_vec[id])
     }
 
@@ -48,11 +49,11 @@ impl Classes {
 pub struct Class {
     pub(crate) id: i32,
     pub(crate) name: Box<str>,
-    pub(crate) serializer: Rc<Serializer>,
+    pub(crate) serializer: Arc<Serializer>,
 }
 
 impl Class {
-    pub(crate) fn new(id: i32, name: Box<str>, serializer: Rc<Serializer>) -> Self {
+    pub(crate) fn new(id: i32, name: Box<str>, serializer: Arc<Serializer>) -> Self {
         Class {
             id,
             name,
@@ -67,6 +68,43 @@ impl Class {
     pub fn id(&self) -> i32 {
         self.id
     }
+
+    /// All fields of the class serializer, nested tables are flattened.
+    pub fn fields(&self) -> Vec<FieldInfo> {
+        let mut fields = vec![];
+        self.serializer.get_field_infos("", &mut fields);
+        fields
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum FieldKind {
+    Simple,
+    /// Array with fixed length, elements are named `name.0000`, `name.0001`
+    /// and so on.
+    FixedArray(i32),
+    /// Vector of values, elements are named the same way as in arrays.
+    VariableArray,
+    /// Nested table, fields are named `name.field`.
+    FixedTable,
+    /// Vector of nested tables, fields are named `name.0000.field`.
+    VariableTable,
+}
+
+/// Field metadata from the flattened serializers.
+#[derive(Debug, Clone)]
+pub struct FieldInfo {
+    /// Property name. Index of the variable table element is replaced with
+    /// `*`, e.g. `m_vecPlayerData.*.m_iszPlayerName`.
+    pub name: String,
+    /// Type from the schema, e.g. `CNetworkUtlVectorBase< CHandle< CBaseEntity > >`.
+    pub field_type: String,
+    pub kind: FieldKind,
+    pub encoder: Option<Encoder>,
+    pub encoder_flags: i32,
+    pub bit_count: i32,
+    pub low_value: f32,
+    pub high_value: f32,
 }
 
 impl Display for Classes {
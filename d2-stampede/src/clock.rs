@@ -0,0 +1,94 @@
+use crate::game_rules::TICKS_PER_SECOND;
+use crate::parser::{Context, Observer};
+use anyhow::Result;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ClockPoint {
+    tick: u32,
+    game_time: f32,
+    paused: bool,
+}
+
+/// Mapping between replay ticks and in-game clock collected during the
+/// parse. Accounts for pre-game, pauses and any other jumps of the clock, so
+/// it can be used to find a tick for [`crate::Parser::jump_to_tick`] after
+/// the first pass:
+///
+/// ```ignore
+/// let clock = parser.register_observer::<ClockMap>();
+/// parser.run_to_end()?;
+/// let tick = clock.borrow().tick_for_game_time(600.0).unwrap();
+/// parser.jump_to_tick(tick)?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ClockMap {
+    points: Vec<ClockPoint>,
+}
+
+impl ClockMap {
+    /// Game time at given tick, `None` before game rules are available.
+    pub fn game_time(&self, tick: u32) -> Option<f32> {
+        let idx = self.points.partition_point(|point| point.tick <= tick);
+        let point = self.points.get(idx.checked_sub(1)?)?;
+        Some(Self::extrapolate(point, tick))
+    }
+
+    /// First tick with game time greater or equal to `secs`.
+    pub fn tick_for_game_time(&self, secs: f32) -> Option<u32> {
+        for (i, point) in self.points.iter().enumerate() {
+            if point.game_time >= secs {
+                return Some(point.tick);
+            }
+            if point.paused {
+                continue;
+            }
+            let tick = point.tick + ((secs - point.game_time) * TICKS_PER_SECOND).ceil() as u32;
+            if self.points.get(i + 1).is_none_or(|next| tick < next.tick) {
+                return Some(tick);
+            }
+        }
+        None
+    }
+
+    /// Ticks where the game was paused.
+    pub fn pauses(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.points
+            .iter()
+            .zip(self.points.iter().skip(1))
+            .filter(|(point, _)| point.paused)
+            .map(|(point, next)| (point.tick, next.tick))
+    }
+
+    fn extrapolate(point: &ClockPoint, tick: u32) -> f32 {
+        if point.paused {
+            point.game_time
+        } else {
+            point.game_time + (tick - point.tick) as f32 / TICKS_PER_SECOND
+        }
+    }
+}
+
+impl Observer for ClockMap {
+    fn on_tick_end(&mut self, ctx: &Context) -> Result<()> {
+        let Ok(rules) = ctx.game_rules() else {
+            return Ok(());
+        };
+        let (Ok(game_time), Ok(paused)) = (rules.game_time(), rules.is_paused()) else {
+            return Ok(());
+        };
+
+        let tick = ctx.tick();
+        let changed = self.points.last().is_none_or(|last| {
+            last.paused != paused
+                || (Self::extrapolate(last, tick) - game_time).abs() > 1.0 / TICKS_PER_SECOND
+        });
+        if changed && self.points.last().is_none_or(|last| last.tick < tick) {
+            self.points.push(ClockPoint {
+                tick,
+                game_time,
+                paused,
+            });
+        }
+        Ok(())
+    }
+}
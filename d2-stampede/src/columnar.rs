@@ -0,0 +1,550 @@
+//! Arrow record batches for combat log, game events and entity properties.
+//! Enabled with `arrow` feature.
+
+use crate::combat_log::CombatLog;
+use crate::decoder::Decoder;
+use crate::entity::Entity;
+use crate::field_value::FieldValue;
+use crate::game_event::GameEvent;
+use crate::parser::{Context, Observer};
+use anyhow::Result;
+use arrow::array::{
+    ArrayRef, BooleanBuilder, Float32Builder, Int32Builder, Int64Builder, StringBuilder,
+    UInt32Builder, UInt64Builder,
+};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use hashbrown::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Default number of rows in a record batch.
+const BATCH_SIZE: usize = 8192;
+
+/// Column builder for [`FieldValue`]. Type is chosen by the field decoder or
+/// the first value, values of other types are converted where possible and
+/// appended as nulls otherwise. Vectors are stored as strings.
+enum ValueColumn {
+    Boolean(BooleanBuilder),
+    Signed(Int64Builder),
+    Unsigned(UInt64Builder),
+    Float(Float32Builder),
+    String(StringBuilder),
+}
+
+impl ValueColumn {
+    fn for_value(value: &FieldValue) -> Self {
+        match value {
+            FieldValue::Boolean(_) => ValueColumn::Boolean(BooleanBuilder::new()),
+            FieldValue::Signed8(_)
+            | FieldValue::Signed16(_)
+            | FieldValue::Signed32(_)
+            | FieldValue::Signed64(_) => ValueColumn::Signed(Int64Builder::new()),
+            FieldValue::Unsigned8(_)
+            | FieldValue::Unsigned16(_)
+            | FieldValue::Unsigned32(_)
+            | FieldValue::Unsigned64(_) => ValueColumn::Unsigned(UInt64Builder::new()),
+            FieldValue::Float(_) => ValueColumn::Float(Float32Builder::new()),
+            _ => ValueColumn::String(StringBuilder::new()),
+        }
+    }
+
+    fn for_decoder(decoder: &Decoder) -> Self {
+        match decoder {
+            Decoder::Boolean | Decoder::Component => ValueColumn::Boolean(BooleanBuilder::new()),
+            Decoder::Signed8 | Decoder::Signed16 | Decoder::Signed32 | Decoder::Signed64 => {
+                ValueColumn::Signed(Int64Builder::new())
+            }
+            Decoder::Unsigned8
+            | Decoder::Unsigned16
+            | Decoder::Unsigned32
+            | Decoder::Unsigned64(_)
+            | Decoder::Fixed64 => ValueColumn::Unsigned(UInt64Builder::new()),
+            Decoder::FloatCoordinate
+            | Decoder::NoScale
+            | Decoder::RuneTime
+            | Decoder::SimulationTime
+            | Decoder::Float32(_)
+            | Decoder::QuantizedFloat(_) => ValueColumn::Float(Float32Builder::new()),
+            _ => ValueColumn::String(StringBuilder::new()),
+        }
+    }
+
+    fn data_type(&self) -> DataType {
+        match self {
+            ValueColumn::Boolean(_) => DataType::Boolean,
+            ValueColumn::Signed(_) => DataType::Int64,
+            ValueColumn::Unsigned(_) => DataType::UInt64,
+            ValueColumn::Float(_) => DataType::Float32,
+            ValueColumn::String(_) => DataType::Utf8,
+        }
+    }
+
+    fn append(&mut self, value: Option<&FieldValue>) {
+        match self {
+            ValueColumn::Boolean(builder) => {
+                builder.append_option(value.and_then(|x| x.try_into().ok()))
+            }
+            ValueColumn::Signed(builder) => {
+                builder.append_option(value.and_then(|x| x.try_into().ok()))
+            }
+            ValueColumn::Unsigned(builder) => {
+                builder.append_option(value.and_then(|x| x.try_into().ok()))
+            }
+            ValueColumn::Float(builder) => {
+                builder.append_option(value.and_then(|x| x.try_into().ok()))
+            }
+            ValueColumn::String(builder) => builder.append_option(value.map(|x| match x {
+                FieldValue::String(s) => s.clone(),
+                x => x.to_string(),
+            })),
+        }
+    }
+
+    fn finish(&mut self) -> ArrayRef {
+        match self {
+            ValueColumn::Boolean(builder) => Arc::new(builder.finish()),
+            ValueColumn::Signed(builder) => Arc::new(builder.finish()),
+            ValueColumn::Unsigned(builder) => Arc::new(builder.finish()),
+            ValueColumn::Float(builder) => Arc::new(builder.finish()),
+            ValueColumn::String(builder) => Arc::new(builder.finish()),
+        }
+    }
+}
+
+/// Columns of combat log entries.
+pub struct CombatLogColumns {
+    len: usize,
+    tick: UInt32Builder,
+    type_: StringBuilder,
+    time: Float32Builder,
+    attacker: StringBuilder,
+    target: StringBuilder,
+    inflictor: StringBuilder,
+    value: UInt32Builder,
+    health: Int32Builder,
+    attacker_team: UInt32Builder,
+    target_team: UInt32Builder,
+    attacker_hero: BooleanBuilder,
+    target_hero: BooleanBuilder,
+    attacker_illusion: BooleanBuilder,
+    target_illusion: BooleanBuilder,
+    location_x: Float32Builder,
+    location_y: Float32Builder,
+}
+
+impl Default for CombatLogColumns {
+    fn default() -> Self {
+        CombatLogColumns {
+            len: 0,
+            tick: UInt32Builder::new(),
+            type_: StringBuilder::new(),
+            time: Float32Builder::new(),
+            attacker: StringBuilder::new(),
+            target: StringBuilder::new(),
+            inflictor: StringBuilder::new(),
+            value: UInt32Builder::new(),
+            health: Int32Builder::new(),
+            attacker_team: UInt32Builder::new(),
+            target_team: UInt32Builder::new(),
+            attacker_hero: BooleanBuilder::new(),
+            target_hero: BooleanBuilder::new(),
+            attacker_illusion: BooleanBuilder::new(),
+            target_illusion: BooleanBuilder::new(),
+            location_x: Float32Builder::new(),
+            location_y: Float32Builder::new(),
+        }
+    }
+}
+
+impl CombatLogColumns {
+    pub fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("tick", DataType::UInt32, false),
+            Field::new("type", DataType::Utf8, false),
+            Field::new("time", DataType::Float32, true),
+            Field::new("attacker", DataType::Utf8, true),
+            Field::new("target", DataType::Utf8, true),
+            Field::new("inflictor", DataType::Utf8, true),
+            Field::new("value", DataType::UInt32, true),
+            Field::new("health", DataType::Int32, true),
+            Field::new("attacker_team", DataType::UInt32, true),
+            Field::new("target_team", DataType::UInt32, true),
+            Field::new("attacker_hero", DataType::Boolean, true),
+            Field::new("target_hero", DataType::Boolean, true),
+            Field::new("attacker_illusion", DataType::Boolean, true),
+            Field::new("target_illusion", DataType::Boolean, true),
+            Field::new("location_x", DataType::Float32, true),
+            Field::new("location_y", DataType::Float32, true),
+        ]))
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn append(&mut self, tick: u32, combat_log: &CombatLog) {
+        self.len += 1;
+        self.tick.append_value(tick);
+        self.type_.append_value(format!("{:?}", combat_log.type_()));
+        self.time.append_option(combat_log.timestamp().ok());
+        self.attacker.append_option(combat_log.attacker_name().ok());
+        self.target.append_option(combat_log.target_name().ok());
+        self.inflictor
+            .append_option(combat_log.inflictor_name().ok());
+        self.value.append_option(combat_log.value().ok());
+        self.health.append_option(combat_log.health().ok());
+        self.attacker_team
+            .append_option(combat_log.attacker_team().ok());
+        self.target_team
+            .append_option(combat_log.target_team().ok());
+        self.attacker_hero
+            .append_option(combat_log.attacker_hero().ok());
+        self.target_hero
+            .append_option(combat_log.target_hero().ok());
+        self.attacker_illusion
+            .append_option(combat_log.attacker_illusion().ok());
+        self.target_illusion
+            .append_option(combat_log.target_illusion().ok());
+        self.location_x.append_option(combat_log.location_x().ok());
+        self.location_y.append_option(combat_log.location_y().ok());
+    }
+
+    /// Moves appended rows into a record batch.
+    pub fn finish(&mut self) -> Result<RecordBatch> {
+        self.len = 0;
+        Ok(RecordBatch::try_new(
+            Self::schema(),
+            vec![
+                Arc::new(self.tick.finish()),
+                Arc::new(self.type_.finish()),
+                Arc::new(self.time.finish()),
+                Arc::new(self.attacker.finish()),
+                Arc::new(self.target.finish()),
+                Arc::new(self.inflictor.finish()),
+                Arc::new(self.value.finish()),
+                Arc::new(self.health.finish()),
+                Arc::new(self.attacker_team.finish()),
+                Arc::new(self.target_team.finish()),
+                Arc::new(self.attacker_hero.finish()),
+                Arc::new(self.target_hero.finish()),
+                Arc::new(self.attacker_illusion.finish()),
+                Arc::new(self.target_illusion.finish()),
+                Arc::new(self.location_x.finish()),
+                Arc::new(self.location_y.finish()),
+            ],
+        )?)
+    }
+}
+
+/// Columns of a table with `tick` column followed by named value columns.
+/// Entity tables take column types from the class serializer, game event
+/// tables from the first appended row.
+struct ValueColumns {
+    len: usize,
+    names: Vec<Box<str>>,
+    tick: UInt32Builder,
+    index: Option<UInt32Builder>,
+    columns: Vec<ValueColumn>,
+}
+
+impl ValueColumns {
+    fn new(names: Vec<Box<str>>, with_index: bool) -> Self {
+        ValueColumns {
+            len: 0,
+            names,
+            tick: UInt32Builder::new(),
+            index: with_index.then(UInt32Builder::new),
+            columns: vec![],
+        }
+    }
+
+    fn schema(&self) -> SchemaRef {
+        let mut fields = vec![Field::new("tick", DataType::UInt32, false)];
+        if self.index.is_some() {
+            fields.push(Field::new("index", DataType::UInt32, false));
+        }
+        fields.extend(
+            self.names
+                .iter()
+                .zip(self.columns.iter())
+                .map(|(name, column)| Field::new(name.as_ref(), column.data_type(), true)),
+        );
+        Arc::new(Schema::new(fields))
+    }
+
+    fn append<'a>(
+        &mut self,
+        tick: u32,
+        index: Option<u32>,
+        values: impl Iterator<Item = Option<&'a FieldValue>>,
+    ) {
+        let values = values.collect::<Vec<_>>();
+        if self.columns.is_empty() {
+            self.columns = values
+                .iter()
+                .map(|value| {
+                    value
+                        .map(ValueColumn::for_value)
+                        .unwrap_or_else(|| ValueColumn::String(StringBuilder::new()))
+                })
+                .collect();
+        }
+
+        self.len += 1;
+        self.tick.append_value(tick);
+        if let Some(builder) = self.index.as_mut() {
+            builder.append_value(index.unwrap_or_default());
+        }
+        for (column, value) in self.columns.iter_mut().zip(values) {
+            column.append(value);
+        }
+    }
+
+    fn finish(&mut self) -> Result<RecordBatch> {
+        self.len = 0;
+        let mut columns: Vec<ArrayRef> = vec![Arc::new(self.tick.finish())];
+        if let Some(builder) = self.index.as_mut() {
+            columns.push(Arc::new(builder.finish()));
+        }
+        columns.extend(self.columns.iter_mut().map(|column| column.finish()));
+        Ok(RecordBatch::try_new(self.schema(), columns)?)
+    }
+}
+
+/// Collects combat log, game events and sampled entity properties into
+/// record batches. Every game event type gets its own table with a column
+/// per event key. Entity tables are sampled every `interval` ticks and have
+/// `tick` and `index` columns followed by requested properties.
+///
+/// ```no_run
+/// use d2_stampede::prelude::*;
+/// use d2_stampede::columnar::ArrowExport;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let replay = std::fs::read("replay.dem")?;
+/// let mut parser = Parser::new(&replay)?;
+/// let export = parser.register_observer::<ArrowExport>();
+/// export
+///     .borrow_mut()
+///     .add_entity_table("CDOTA_Unit_Hero_Axe", &["m_iHealth", "m_iCurrentLevel"]);
+/// parser.run_to_end()?;
+/// export.borrow_mut().write_parquet("out")?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct ArrowExport {
+    batch_size: usize,
+    interval: u32,
+
+    combat_log: CombatLogColumns,
+    combat_log_batches: Vec<RecordBatch>,
+
+    game_events: HashMap<Box<str>, ValueColumns>,
+    game_event_batches: HashMap<Box<str>, Vec<RecordBatch>>,
+
+    entity_tables: HashMap<Box<str>, ValueColumns>,
+    entity_batches: HashMap<Box<str>, Vec<RecordBatch>>,
+}
+
+impl Default for ArrowExport {
+    fn default() -> Self {
+        ArrowExport {
+            batch_size: BATCH_SIZE,
+            interval: 30,
+            combat_log: CombatLogColumns::default(),
+            combat_log_batches: vec![],
+            game_events: HashMap::default(),
+            game_event_batches: HashMap::default(),
+            entity_tables: HashMap::default(),
+            entity_batches: HashMap::default(),
+        }
+    }
+}
+
+impl ArrowExport {
+    /// Number of rows in a record batch, `8192` by default.
+    pub fn set_batch_size(&mut self, batch_size: usize) {
+        self.batch_size = batch_size;
+    }
+
+    /// Ticks between entity samples, `30` by default.
+    pub fn set_interval(&mut self, ticks: u32) {
+        self.interval = ticks;
+    }
+
+    /// Samples given properties of entities with given class name.
+    pub fn add_entity_table(&mut self, class: &str, properties: &[&str]) {
+        self.entity_tables.insert(
+            class.into(),
+            ValueColumns::new(properties.iter().map(|&x| x.into()).collect(), true),
+        );
+    }
+
+    /// Finishes pending rows and returns combat log batches.
+    pub fn combat_log_batches(&mut self) -> Result<&[RecordBatch]> {
+        self.flush()?;
+        Ok(&self.combat_log_batches)
+    }
+
+    /// Finishes pending rows and returns batches of the game event type.
+    pub fn game_event_batches(&mut self, name: &str) -> Result<&[RecordBatch]> {
+        self.flush()?;
+        Ok(self
+            .game_event_batches
+            .get(name)
+            .map(|x| x.as_slice())
+            .unwrap_or_default())
+    }
+
+    /// Finishes pending rows and returns batches of the entity class.
+    pub fn entity_batches(&mut self, class: &str) -> Result<&[RecordBatch]> {
+        self.flush()?;
+        Ok(self
+            .entity_batches
+            .get(class)
+            .map(|x| x.as_slice())
+            .unwrap_or_default())
+    }
+
+    /// Writes every table into `dir` as `combat_log.parquet`,
+    /// `game_event_<name>.parquet` and `entity_<class>.parquet`.
+    pub fn write_parquet(&mut self, dir: impl AsRef<Path>) -> Result<()> {
+        self.flush()?;
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+
+        write_parquet(
+            std::fs::File::create(dir.join("combat_log.parquet"))?,
+            CombatLogColumns::schema(),
+            &self.combat_log_batches,
+        )?;
+        for (name, batches) in self.game_event_batches.iter() {
+            if let Some(batch) = batches.first() {
+                write_parquet(
+                    std::fs::File::create(dir.join(format!("game_event_{name}.parquet")))?,
+                    batch.schema(),
+                    batches,
+                )?;
+            }
+        }
+        for (class, batches) in self.entity_batches.iter() {
+            if let Some(batch) = batches.first() {
+                write_parquet(
+                    std::fs::File::create(dir.join(format!("entity_{class}.parquet")))?,
+                    batch.schema(),
+                    batches,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        if !self.combat_log.is_empty() {
+            self.combat_log_batches.push(self.combat_log.finish()?);
+        }
+        for (name, columns) in self.game_events.iter_mut() {
+            if columns.len > 0 {
+                self.game_event_batches
+                    .entry(name.clone())
+                    .or_default()
+                    .push(columns.finish()?);
+            }
+        }
+        for (class, columns) in self.entity_tables.iter_mut() {
+            if columns.len > 0 {
+                self.entity_batches
+                    .entry(class.clone())
+                    .or_default()
+                    .push(columns.finish()?);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Observer for ArrowExport {
+    fn on_combat_log(&mut self, ctx: &Context, combat_log: &CombatLog) -> Result<()> {
+        self.combat_log.append(ctx.tick(), combat_log);
+        if self.combat_log.len() >= self.batch_size {
+            self.combat_log_batches.push(self.combat_log.finish()?);
+        }
+        Ok(())
+    }
+
+    fn on_game_event(&mut self, ctx: &Context, event: &GameEvent) -> Result<()> {
+        let columns = self
+            .game_events
+            .entry(event.name().into())
+            .or_insert_with(|| {
+                ValueColumns::new(event.iter().map(|(key, _)| key.into()).collect(), false)
+            });
+        columns.append(ctx.tick(), None, event.iter().map(|(_, value)| Some(value)));
+        if columns.len >= self.batch_size {
+            self.game_event_batches
+                .entry(event.name().into())
+                .or_default()
+                .push(columns.finish()?);
+        }
+        Ok(())
+    }
+
+    fn interval(&self) -> Option<u32> {
+        (!self.entity_tables.is_empty()).then_some(self.interval)
+    }
+
+    fn on_interval(&mut self, ctx: &Context) -> Result<()> {
+        for (class, columns) in self.entity_tables.iter_mut() {
+            for entity in ctx.entities().get_all_by_class_name(class) {
+                append_entity(columns, ctx.tick(), entity);
+            }
+            if columns.len >= self.batch_size {
+                self.entity_batches
+                    .entry(class.clone())
+                    .or_default()
+                    .push(columns.finish()?);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn append_entity(columns: &mut ValueColumns, tick: u32, entity: &Entity) {
+    if columns.columns.is_empty() {
+        let serializer = &entity.class().serializer;
+        columns.columns = columns
+            .names
+            .iter()
+            .map(|name| match serializer.get_field_path_for_name(name) {
+                Ok(fp) => ValueColumn::for_decoder(serializer.get_decoder_for_field_path(&fp)),
+                Err(_) => ValueColumn::String(StringBuilder::new()),
+            })
+            .collect();
+    }
+    let values = columns
+        .names
+        .iter()
+        .map(|name| entity.get_property_by_name(name).ok())
+        .collect::<Vec<_>>();
+    columns.append(tick, Some(entity.index()), values.into_iter());
+}
+
+/// Writes record batches with given schema as a Parquet file.
+pub fn write_parquet<W: std::io::Write + Send>(
+    writer: W,
+    schema: SchemaRef,
+    batches: &[RecordBatch],
+) -> Result<()> {
+    let mut writer = parquet::arrow::ArrowWriter::try_new(writer, schema, None)?;
+    for batch in batches {
+        writer.write(batch)?;
+    }
+    writer.close()?;
+    Ok(())
+}
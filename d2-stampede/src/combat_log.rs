@@ -1,7 +1,229 @@
-use crate::proto::{CMsgDotaCombatLogEntry, DotaCombatlogTypes};
+use crate::proto::{CMsgDotaCombatLogEntry, DotaCombatlogTypes, DotaGameState};
 use crate::string_table::StringTable;
 use anyhow::{anyhow, Context, Result};
 
+/// Combat log entry with names resolved from `CombatLogNames` string table.
+/// Names missing from the table are `None`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CombatLogEntry<'a> {
+    Damage {
+        time: f32,
+        attacker: Option<&'a str>,
+        target: Option<&'a str>,
+        inflictor: Option<&'a str>,
+        damage: u32,
+        health: i32,
+        target_hero: bool,
+        target_illusion: bool,
+    },
+    Heal {
+        time: f32,
+        attacker: Option<&'a str>,
+        target: Option<&'a str>,
+        inflictor: Option<&'a str>,
+        amount: u32,
+        health: i32,
+        target_hero: bool,
+    },
+    ModifierAdd {
+        time: f32,
+        attacker: Option<&'a str>,
+        target: Option<&'a str>,
+        modifier: Option<&'a str>,
+        target_hero: bool,
+    },
+    ModifierRemove {
+        time: f32,
+        target: Option<&'a str>,
+        modifier: Option<&'a str>,
+        target_hero: bool,
+    },
+    Death {
+        time: f32,
+        attacker: Option<&'a str>,
+        target: Option<&'a str>,
+        inflictor: Option<&'a str>,
+        target_hero: bool,
+        target_illusion: bool,
+    },
+    AbilityUse {
+        time: f32,
+        attacker: Option<&'a str>,
+        ability: Option<&'a str>,
+        level: u32,
+        target: Option<&'a str>,
+    },
+    ItemUse {
+        time: f32,
+        attacker: Option<&'a str>,
+        item: Option<&'a str>,
+    },
+    ItemPurchase {
+        time: f32,
+        buyer: Option<&'a str>,
+        item: Option<&'a str>,
+    },
+    GoldChange {
+        time: f32,
+        target: Option<&'a str>,
+        amount: i32,
+        reason: u32,
+    },
+    XpGain {
+        time: f32,
+        target: Option<&'a str>,
+        amount: u32,
+        reason: u32,
+    },
+    GameState {
+        time: f32,
+        state: DotaGameState,
+    },
+    Other {
+        time: f32,
+        type_: DotaCombatlogTypes,
+    },
+}
+
+impl<'a> CombatLogEntry<'a> {
+    /// Game time of the entry in seconds, as reported by the server.
+    pub fn game_time(&self) -> f32 {
+        match self {
+            CombatLogEntry::Damage { time, .. }
+            | CombatLogEntry::Heal { time, .. }
+            | CombatLogEntry::ModifierAdd { time, .. }
+            | CombatLogEntry::ModifierRemove { time, .. }
+            | CombatLogEntry::Death { time, .. }
+            | CombatLogEntry::AbilityUse { time, .. }
+            | CombatLogEntry::ItemUse { time, .. }
+            | CombatLogEntry::ItemPurchase { time, .. }
+            | CombatLogEntry::GoldChange { time, .. }
+            | CombatLogEntry::XpGain { time, .. }
+            | CombatLogEntry::GameState { time, .. }
+            | CombatLogEntry::Other { time, .. } => *time,
+        }
+    }
+
+    pub fn attacker_name(&self) -> Option<&'a str> {
+        match self {
+            CombatLogEntry::Damage { attacker, .. }
+            | CombatLogEntry::Heal { attacker, .. }
+            | CombatLogEntry::ModifierAdd { attacker, .. }
+            | CombatLogEntry::Death { attacker, .. }
+            | CombatLogEntry::AbilityUse { attacker, .. }
+            | CombatLogEntry::ItemUse { attacker, .. } => *attacker,
+            _ => None,
+        }
+    }
+
+    pub fn target_name(&self) -> Option<&'a str> {
+        match self {
+            CombatLogEntry::Damage { target, .. }
+            | CombatLogEntry::Heal { target, .. }
+            | CombatLogEntry::ModifierAdd { target, .. }
+            | CombatLogEntry::ModifierRemove { target, .. }
+            | CombatLogEntry::Death { target, .. }
+            | CombatLogEntry::GoldChange { target, .. }
+            | CombatLogEntry::XpGain { target, .. }
+            | CombatLogEntry::AbilityUse { target, .. } => *target,
+            CombatLogEntry::ItemPurchase { buyer, .. } => *buyer,
+            _ => None,
+        }
+    }
+
+    /// English name of the attacker if it's a hero, combat log name
+    /// otherwise.
+    #[cfg(feature = "names")]
+    pub fn attacker_display_name(&self) -> Option<&'a str> {
+        self.attacker_name().map(crate::Names::localized)
+    }
+
+    /// English name of the target if it's a hero, combat log name otherwise.
+    #[cfg(feature = "names")]
+    pub fn target_display_name(&self) -> Option<&'a str> {
+        self.target_name().map(crate::Names::localized)
+    }
+
+    pub fn is_hero_target(&self) -> bool {
+        match self {
+            CombatLogEntry::Damage { target_hero, .. }
+            | CombatLogEntry::Heal { target_hero, .. }
+            | CombatLogEntry::ModifierAdd { target_hero, .. }
+            | CombatLogEntry::ModifierRemove { target_hero, .. }
+            | CombatLogEntry::Death { target_hero, .. } => *target_hero,
+            _ => false,
+        }
+    }
+}
+
+/// Set of combat log types, see [`crate::Observer::combat_log_types`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct CombatLogTypes(u64);
+
+impl CombatLogTypes {
+    pub const ALL: CombatLogTypes = CombatLogTypes(u64::MAX);
+    pub const NONE: CombatLogTypes = CombatLogTypes(0);
+
+    pub fn new(types: &[DotaCombatlogTypes]) -> Self {
+        types.iter().fold(Self::NONE, |acc, &type_| acc.with(type_))
+    }
+
+    pub fn with(self, type_: DotaCombatlogTypes) -> Self {
+        match Self::bit(type_ as i32) {
+            Some(bit) => CombatLogTypes(self.0 | bit),
+            None => Self::ALL,
+        }
+    }
+
+    pub fn union(self, other: CombatLogTypes) -> Self {
+        CombatLogTypes(self.0 | other.0)
+    }
+
+    pub fn contains(&self, type_: DotaCombatlogTypes) -> bool {
+        self.contains_raw(type_ as i32)
+    }
+
+    pub(crate) fn contains_raw(&self, type_: i32) -> bool {
+        match Self::bit(type_) {
+            Some(bit) => self.0 & bit != 0,
+            None => *self == Self::ALL,
+        }
+    }
+
+    fn bit(type_: i32) -> Option<u64> {
+        (0..64).contains(&type_).then(|| 1 << type_)
+    }
+}
+
+impl From<DotaCombatlogTypes> for CombatLogTypes {
+    fn from(type_: DotaCombatlogTypes) -> Self {
+        Self::NONE.with(type_)
+    }
+}
+
+impl Default for CombatLogTypes {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+/// Reads entry type from encoded `CMsgDOTACombatLogEntry` without decoding
+/// it. Type is the first field, so it's only checked at the start.
+pub(crate) fn peek_combat_log_type(msg: &[u8]) -> Option<i32> {
+    let (&tag, rest) = msg.split_first()?;
+    if tag != 0x08 {
+        return None;
+    }
+    let mut value = 0u64;
+    for (i, &byte) in rest.iter().take(10).enumerate() {
+        value |= ((byte & 0x7f) as u64) << (i * 7);
+        if byte & 0x80 == 0 {
+            return Some(value as i32);
+        }
+    }
+    None
+}
+
 #[derive(Clone)]
 pub struct CombatLog<'a> {
     pub(crate) names: &'a StringTable,
@@ -13,6 +235,98 @@ impl<'a> CombatLog<'a> {
         self.log.r#type()
     }
 
+    /// Returns typed representation of the entry.
+    pub fn entry(&self) -> Result<CombatLogEntry<'a>> {
+        let time = self.timestamp()?;
+        let target_hero = self.log.is_target_hero.unwrap_or(false);
+        let target_illusion = self.log.is_target_illusion.unwrap_or(false);
+
+        let entry = match self.type_() {
+            DotaCombatlogTypes::DotaCombatlogDamage => CombatLogEntry::Damage {
+                time,
+                attacker: self.resolve(self.log.attacker_name),
+                target: self.resolve(self.log.target_name),
+                inflictor: self.resolve(self.log.inflictor_name),
+                damage: self.value()?,
+                health: self.health()?,
+                target_hero,
+                target_illusion,
+            },
+            DotaCombatlogTypes::DotaCombatlogHeal => CombatLogEntry::Heal {
+                time,
+                attacker: self.resolve(self.log.attacker_name),
+                target: self.resolve(self.log.target_name),
+                inflictor: self.resolve(self.log.inflictor_name),
+                amount: self.value()?,
+                health: self.health()?,
+                target_hero,
+            },
+            DotaCombatlogTypes::DotaCombatlogModifierAdd => CombatLogEntry::ModifierAdd {
+                time,
+                attacker: self.resolve(self.log.attacker_name),
+                target: self.resolve(self.log.target_name),
+                modifier: self.resolve(self.log.inflictor_name),
+                target_hero,
+            },
+            DotaCombatlogTypes::DotaCombatlogModifierRemove => CombatLogEntry::ModifierRemove {
+                time,
+                target: self.resolve(self.log.target_name),
+                modifier: self.resolve(self.log.inflictor_name),
+                target_hero,
+            },
+            DotaCombatlogTypes::DotaCombatlogDeath => CombatLogEntry::Death {
+                time,
+                attacker: self.resolve(self.log.attacker_name),
+                target: self.resolve(self.log.target_name),
+                inflictor: self.resolve(self.log.inflictor_name),
+                target_hero,
+                target_illusion,
+            },
+            DotaCombatlogTypes::DotaCombatlogAbility => CombatLogEntry::AbilityUse {
+                time,
+                attacker: self.resolve(self.log.attacker_name),
+                ability: self.resolve(self.log.inflictor_name),
+                level: self.ability_level().unwrap_or_default(),
+                target: self.resolve(self.log.target_name),
+            },
+            DotaCombatlogTypes::DotaCombatlogItem => CombatLogEntry::ItemUse {
+                time,
+                attacker: self.resolve(self.log.attacker_name),
+                item: self.resolve(self.log.inflictor_name),
+            },
+            DotaCombatlogTypes::DotaCombatlogPurchase => CombatLogEntry::ItemPurchase {
+                time,
+                buyer: self.resolve(self.log.target_name),
+                item: self.resolve(self.log.value),
+            },
+            DotaCombatlogTypes::DotaCombatlogGold => CombatLogEntry::GoldChange {
+                time,
+                target: self.resolve(self.log.target_name),
+                amount: self.value()? as i32,
+                reason: self.gold_reason().unwrap_or_default(),
+            },
+            DotaCombatlogTypes::DotaCombatlogXp => CombatLogEntry::XpGain {
+                time,
+                target: self.resolve(self.log.target_name),
+                amount: self.value()?,
+                reason: self.xp_reason().unwrap_or_default(),
+            },
+            DotaCombatlogTypes::DotaCombatlogGameState => CombatLogEntry::GameState {
+                time,
+                state: DotaGameState::try_from(self.value()? as i32)?,
+            },
+            type_ => CombatLogEntry::Other { time, type_ },
+        };
+
+        Ok(entry)
+    }
+
+    fn resolve(&self, id: Option<u32>) -> Option<&'a str> {
+        let names: &'a StringTable = self.names;
+        id.and_then(|id| names.items.get(id as usize))
+            .map(|name| name.key.as_str())
+    }
+
     pub fn target_name(&self) -> Result<&str> {
         self.log
             .target_name
@@ -0,0 +1,40 @@
+use anyhow::Result;
+use std::borrow::Cow;
+use std::io::Read;
+
+const BZIP2_MAGIC: &[u8] = b"BZh";
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+
+/// Decompresses `.dem.bz2` or gzip replay, other data is returned as is.
+/// Result can be passed to [`crate::Parser::new`].
+pub fn decompress(replay: &[u8]) -> Result<Cow<'_, [u8]>> {
+    if !replay.starts_with(BZIP2_MAGIC) && !replay.starts_with(GZIP_MAGIC) {
+        return Ok(Cow::Borrowed(replay));
+    }
+    Ok(Cow::Owned(read_replay(replay)?))
+}
+
+/// Reads the whole replay from `reader`, e.g. HTTP response, decompressing
+/// it on the fly if it starts with bzip2 or gzip magic bytes.
+pub fn read_replay<R: Read>(mut reader: R) -> Result<Vec<u8>> {
+    let mut magic = [0u8; 3];
+    let mut n = 0;
+    while n < magic.len() {
+        match reader.read(&mut magic[n..])? {
+            0 => break,
+            x => n += x,
+        }
+    }
+    let reader = (&magic[..n]).chain(reader);
+
+    let mut buf = vec![];
+    if magic[..n].starts_with(BZIP2_MAGIC) {
+        bzip2::read::MultiBzDecoder::new(reader).read_to_end(&mut buf)?;
+    } else if magic[..n].starts_with(GZIP_MAGIC) {
+        flate2::read::MultiGzDecoder::new(reader).read_to_end(&mut buf)?;
+    } else {
+        let mut reader = reader;
+        reader.read_to_end(&mut buf)?;
+    }
+    Ok(buf)
+}
@@ -1,6 +1,106 @@
 use crate::field::{Encoder, FieldProperties, FieldType};
 use crate::field_value::FieldValue;
 use crate::reader::Reader;
+use hashbrown::HashMap;
+use std::sync::Arc;
+
+/// Custom decoder for fields with encoders or types the parser doesn't know
+/// about, see [`DecoderRegistry`].
+pub trait FieldDecoder: Send + Sync {
+    fn decode(&self, reader: &mut DecoderReader, properties: &FieldProperties) -> FieldValue;
+}
+
+/// Bit reader passed to [`FieldDecoder::decode`].
+pub struct DecoderReader<'r, 'a> {
+    reader: &'r mut Reader<'a>,
+}
+
+impl DecoderReader<'_, '_> {
+    /// Reads up to 32 bits.
+    pub fn read_bits(&mut self, amount: u32) -> u32 {
+        self.reader.read_bits(amount)
+    }
+
+    pub fn read_bool(&mut self) -> bool {
+        self.reader.read_bits(1) == 1
+    }
+
+    pub fn read_f32(&mut self) -> f32 {
+        self.reader.read_f32()
+    }
+
+    pub fn read_var_u32(&mut self) -> u32 {
+        self.reader.read_var_u32()
+    }
+
+    pub fn read_var_i32(&mut self) -> i32 {
+        self.reader.read_var_i32()
+    }
+
+    pub fn read_var_u64(&mut self) -> u64 {
+        self.reader.read_var_u64()
+    }
+
+    pub fn read_coordinate(&mut self) -> f32 {
+        self.reader.read_coordinate()
+    }
+
+    /// Reads `n` bits angle, `n` must not exceed 32.
+    pub fn read_angle(&mut self, n: u32) -> f32 {
+        self.reader.refill();
+        self.reader.read_angle(n)
+    }
+
+    pub fn read_string(&mut self) -> String {
+        self.reader.read_string()
+    }
+}
+
+/// Custom field decoders, set with [`crate::Parser::set_decoders`]. Encoder
+/// names (`var_encoder` in send tables, e.g. `qangle_precise`) are checked
+/// first, then field types (e.g. `QAngle`). Fields without custom decoders
+/// use built-in ones.
+#[derive(Clone, Default)]
+pub struct DecoderRegistry {
+    encoders: HashMap<Box<str>, Arc<dyn FieldDecoder>>,
+    types: HashMap<Box<str>, Arc<dyn FieldDecoder>>,
+}
+
+impl DecoderRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register_encoder(&mut self, encoder: &str, decoder: impl FieldDecoder + 'static) {
+        self.encoders.insert(encoder.into(), Arc::new(decoder));
+    }
+
+    /// Registers decoder for base type name, e.g. `QAngle` for both `QAngle`
+    /// and `QAngle[2]`.
+    pub fn register_type(&mut self, field_type: &str, decoder: impl FieldDecoder + 'static) {
+        self.types.insert(field_type.into(), Arc::new(decoder));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.encoders.is_empty() && self.types.is_empty()
+    }
+
+    pub(crate) fn decoder(
+        &self,
+        field_type: &FieldType,
+        encoder: &str,
+        properties: FieldProperties,
+    ) -> Decoder {
+        if let Some(decoder) = self
+            .encoders
+            .get(encoder)
+            .or_else(|| self.types.get(field_type.base.as_ref()))
+        {
+            return Decoder::Custom(decoder.clone(), properties);
+        }
+        Decoder::from_field(field_type, properties)
+    }
+}
 
 pub enum Decoder {
     VectorNormal,
@@ -25,6 +125,8 @@ pub enum Decoder {
     Float32(FieldProperties),
     QuantizedFloat(FieldProperties),
     QAngle(FieldProperties),
+
+    Custom(Arc<dyn FieldDecoder>, FieldProperties),
 }
 
 impl Decoder {
@@ -65,6 +167,18 @@ impl Decoder {
         }
     }
 
+    /// Consumes the value without building it. Only strings are cheaper to
+    /// skip, other values have variable length and are read anyway.
+    #[inline(always)]
+    pub(crate) fn skip(&self, reader: &mut Reader) {
+        match self {
+            Decoder::String => reader.skip_string(),
+            _ => {
+                self.decode(reader);
+            }
+        }
+    }
+
     #[inline(always)]
     pub(crate) fn decode(&self, reader: &mut Reader) -> FieldValue {
         match self {
@@ -140,7 +254,7 @@ impl Decoder {
             Decoder::QAngle(fp) => {
                 reader.refill();
                 if fp.encoder == Some(Encoder::QAnglePitchYaw) {
-                    return FieldValue::Vector3D([
+                    return FieldValue::QAngle([
                         reader.read_angle(fp.bit_count as u32),
                         reader.read_angle(fp.bit_count as u32),
                         0.0,
@@ -149,7 +263,7 @@ impl Decoder {
 
                 if fp.bit_count != 0 {
                     let n = fp.bit_count as u32;
-                    return FieldValue::Vector3D([
+                    return FieldValue::QAngle([
                         reader.read_angle(n),
                         reader.read_angle(n),
                         reader.read_angle(n),
@@ -169,8 +283,9 @@ impl Decoder {
                 if z {
                     v[2] = reader.read_coordinate();
                 }
-                FieldValue::Vector3D(v)
+                FieldValue::QAngle(v)
             }
+            Decoder::Custom(decoder, fp) => decoder.decode(&mut DecoderReader { reader }, fp),
         }
     }
 }
@@ -0,0 +1,60 @@
+use crate::combat_log::{peek_combat_log_type, CombatLog};
+use crate::game::{Dispatch, GameMessages};
+use crate::proto::*;
+use crate::user_messages::dispatch_dota_user_message;
+use anyhow::Result;
+use std::collections::VecDeque;
+
+/// Dota user messages and combat log. Combat log entries are collected
+/// during the tick and passed to [`crate::Observer::on_combat_log`] at the end
+/// of it, once `CombatLogNames` table is updated.
+#[derive(Default)]
+pub struct DotaMessages {
+    combat_log: VecDeque<CMsgDotaCombatLogEntry>,
+}
+
+impl GameMessages for DotaMessages {
+    fn pointer_types(&self) -> &[&str] {
+        &[
+            "CDOTAGamerules",
+            "CDOTAGameManager",
+            "CDOTASpectatorGraphManager",
+            "CDOTAGameRules",
+        ]
+    }
+
+    fn message_name(&self, msg_type: i32) -> Option<String> {
+        EDotaUserMessages::try_from(msg_type)
+            .ok()
+            .map(|msg_type| format!("{:?}", msg_type))
+    }
+
+    fn on_message(&mut self, dispatch: &Dispatch, msg_type: i32, msg: &[u8]) -> Result<()> {
+        let msg_type = EDotaUserMessages::try_from(msg_type)?;
+
+        if msg_type == EDotaUserMessages::DotaUmCombatLogDataHltv
+            && peek_combat_log_type(msg).is_none_or(|x| dispatch.combat_log_mask.contains_raw(x))
+        {
+            let entry = CMsgDotaCombatLogEntry::decode(msg)?;
+            if dispatch.combat_log_mask.contains(entry.r#type()) {
+                self.combat_log.push_back(entry);
+            }
+        }
+
+        dispatch.observers.iter().try_for_each(|obs| {
+            obs.borrow_mut()
+                .on_dota_user_message(dispatch.context, msg_type, msg)
+        })?;
+        dispatch_dota_user_message(dispatch.observers, dispatch.context, msg_type, msg)
+    }
+
+    fn on_tick_end(&mut self, dispatch: &Dispatch) -> Result<()> {
+        if let Ok(names) = dispatch.context.string_tables.get_by_name("CombatLogNames") {
+            while let Some(entry) = self.combat_log.pop_front() {
+                let log = CombatLog { names, log: entry };
+                dispatch.notify_combat_log(&log)?;
+            }
+        }
+        Ok(())
+    }
+}
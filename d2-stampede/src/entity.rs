@@ -1,10 +1,16 @@
 use crate::class::Class;
 use crate::field::{FieldPath, FieldVector};
 use crate::field_value::FieldValue;
-use anyhow::{anyhow, Context, Result};
+use crate::snapshot::Snapshot;
+use anyhow::{anyhow, bail, Context, Result};
+use hashbrown::HashMap;
 use prettytable::{row, Table};
+use std::collections::BTreeSet;
 use std::fmt::{Debug, Display, Formatter};
-use std::rc::Rc;
+use std::sync::Arc;
+
+const CELL_SIZE: f32 = 128.0;
+const WORLD_OFFSET: f32 = 16384.0;
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum EntityEvents {
@@ -15,14 +21,100 @@ pub enum EntityEvents {
     Left = 1 << 4,
 }
 
+/// Entity handle as stored in properties like `m_hOwnerEntity` or
+/// `m_hSelectedHero`. Lower 14 bits are entity index, next 10 bits are serial.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct EHandle(u32);
+
+impl EHandle {
+    pub fn new(handle: u32) -> Self {
+        EHandle(handle)
+    }
+
+    /// Returns true for `16777215` and `u32::MAX`, which are used for empty
+    /// handles.
+    pub fn is_null(&self) -> bool {
+        self.index() == 0x3fff
+    }
+
+    pub fn index(&self) -> u32 {
+        self.0 & 0x3fff
+    }
+
+    pub fn serial(&self) -> u32 {
+        (self.0 >> 14) & 0x3ff
+    }
+
+    pub fn raw(&self) -> u32 {
+        self.0
+    }
+}
+
+impl From<u32> for EHandle {
+    fn from(handle: u32) -> Self {
+        EHandle(handle)
+    }
+}
+
+impl Display for EHandle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({}:{})", self.0, self.index(), self.serial())
+    }
+}
+
+/// Cloning is cheap, entity states are shared until they are updated, so it
+/// can be used to keep snapshots of all entities.
+#[derive(Clone)]
 pub struct Entities {
     pub(crate) entities_vec: Vec<Option<Entity>>,
+    /// Indices of entities by class id.
+    by_class: HashMap<i32, BTreeSet<u32>>,
 }
 
 impl Entities {
     pub(crate) fn new() -> Self {
         Entities {
             entities_vec: vec![],
+            by_class: HashMap::default(),
+        }
+    }
+
+    pub(crate) fn insert(&mut self, entity: Entity) -> &mut Entity {
+        let index = entity.index;
+        self.remove(index);
+        self.by_class
+            .entry(entity.class.id)
+            .or_default()
+            .insert(index);
+        self.entities_vec[index as usize].insert(entity)
+    }
+
+    pub(crate) fn remove(&mut self, index: u32) {
+        let Some(entity) = self.entities_vec[index as usize].take() else {
+            return;
+        };
+        if let Some(indices) = self.by_class.get_mut(&entity.class.id) {
+            indices.remove(&index);
+            if indices.is_empty() {
+                self.by_class.remove(&entity.class.id);
+            }
+        }
+    }
+
+    /// Owned copy of current entities that can be kept between ticks and
+    /// compared with [`Snapshot::diff`].
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot::new(self.clone())
+    }
+
+    /// Starts a filtered lookup, e.g.
+    /// `entities.query().class_prefix("CDOTA_Unit_Hero_").team(3).iter()`.
+    /// Class filters use internal index and don't scan all entities.
+    pub fn query(&self) -> EntityQuery<'_> {
+        EntityQuery {
+            entities: self,
+            class_filters: vec![],
+            filters: vec![],
         }
     }
 
@@ -42,25 +134,145 @@ impl Entities {
             .with_context(|| anyhow!("No entities for handle \"{handle}\""))
     }
 
+    /// Returns entity for given handle if it exists and its serial matches
+    /// the handle.
+    pub fn get(&self, handle: &EHandle) -> Result<&Entity> {
+        if handle.is_null() {
+            bail!("Handle {} is null", handle);
+        }
+        let entity = self.get_by_index(handle.index() as usize)?;
+        if entity.serial & 0x3ff != handle.serial() {
+            bail!(
+                "Serial mismatch for handle {}, entity serial is {}",
+                handle,
+                entity.serial
+            );
+        }
+        Ok(entity)
+    }
+
     pub fn get_by_class_id(&self, id: i32) -> Result<&Entity> {
-        self.iter()
-            .find(|&entity| entity.class().id() == id)
+        self.get_all_by_class_id(id)
+            .next()
             .with_context(|| anyhow!("No entities for class with id {id}"))
     }
 
     pub fn get_by_class_name(&self, name: &str) -> Result<&Entity> {
-        self.iter()
-            .find(|&entity| entity.class().name() == name)
+        self.by_class
+            .values()
+            .find(|indices| {
+                self.class_of(indices)
+                    .is_some_and(|class| class.name() == name)
+            })
+            .and_then(|indices| self.entities_vec[*indices.first()? as usize].as_ref())
             .with_context(|| anyhow!("No entities for class with name {name}"))
     }
 
     pub fn get_all_by_class_id(&self, id: i32) -> impl Iterator<Item = &Entity> {
-        self.iter().filter(move |&entity| entity.class().id() == id)
+        self.by_class
+            .get(&id)
+            .into_iter()
+            .flatten()
+            .filter_map(|&index| self.entities_vec[index as usize].as_ref())
     }
 
-    pub fn get_all_by_class_name<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &Entity> {
-        self.iter()
-            .filter(move |&entity| entity.class().name() == name)
+    pub fn get_all_by_class_name<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Entity> {
+        self.query().class_name(name).iter()
+    }
+
+    fn class_of(&self, indices: &BTreeSet<u32>) -> Option<&Class> {
+        let index = *indices.first()?;
+        Some(self.entities_vec[index as usize].as_ref()?.class())
+    }
+}
+
+type ClassFilter<'a> = Box<dyn Fn(&Class) -> bool + 'a>;
+type EntityFilter<'a> = Box<dyn Fn(&Entity) -> bool + 'a>;
+
+/// Filtered lookup of entities, see [`Entities::query`].
+pub struct EntityQuery<'a> {
+    entities: &'a Entities,
+    class_filters: Vec<ClassFilter<'a>>,
+    filters: Vec<EntityFilter<'a>>,
+}
+
+impl<'a> EntityQuery<'a> {
+    pub fn class_id(mut self, id: i32) -> Self {
+        self.class_filters
+            .push(Box::new(move |class| class.id() == id));
+        self
+    }
+
+    pub fn class_name(mut self, name: &'a str) -> Self {
+        self.class_filters
+            .push(Box::new(move |class| class.name() == name));
+        self
+    }
+
+    pub fn class_prefix(mut self, prefix: &'a str) -> Self {
+        self.class_filters
+            .push(Box::new(move |class| class.name().starts_with(prefix)));
+        self
+    }
+
+    /// Entities with `m_iTeamNum` equal to `team`.
+    pub fn team(self, team: i32) -> Self {
+        self.property_eq("m_iTeamNum", team)
+    }
+
+    /// Entities that have the property and its value equals `value`.
+    pub fn property_eq<T>(mut self, name: &'a str, value: T) -> Self
+    where
+        T: PartialEq + 'a,
+        for<'b> &'b FieldValue: TryInto<T, Error = anyhow::Error>,
+    {
+        self.filters.push(Box::new(move |entity| {
+            entity
+                .get_property_by_name(name)
+                .ok()
+                .and_then(|x| x.try_into().ok())
+                .is_some_and(|x: T| x == value)
+        }));
+        self
+    }
+
+    pub fn filter(mut self, f: impl Fn(&Entity) -> bool + 'a) -> Self {
+        self.filters.push(Box::new(f));
+        self
+    }
+
+    /// Matching entities. Without class filters entities are ordered by
+    /// index, otherwise by index within each class.
+    pub fn iter(self) -> impl Iterator<Item = &'a Entity> {
+        let entities = self.entities;
+        let candidates: Box<dyn Iterator<Item = &'a Entity> + 'a> = if self.class_filters.is_empty()
+        {
+            Box::new(entities.iter())
+        } else {
+            let class_filters = self.class_filters;
+            Box::new(
+                entities
+                    .by_class
+                    .values()
+                    .filter(move |indices| {
+                        entities
+                            .class_of(indices)
+                            .is_some_and(|class| class_filters.iter().all(|f| f(class)))
+                    })
+                    .flatten()
+                    .filter_map(|&index| entities.entities_vec[index as usize].as_ref()),
+            )
+        };
+        let filters = self.filters;
+        candidates.filter(move |entity| filters.iter().all(|f| f(entity)))
+    }
+
+    pub fn first(self) -> Option<&'a Entity> {
+        self.iter().next()
+    }
+
+    pub fn count(self) -> usize {
+        self.iter().count()
     }
 }
 
@@ -68,12 +280,12 @@ impl Entities {
 pub struct Entity {
     index: u32,
     serial: u32,
-    pub(crate) class: Rc<Class>,
+    pub(crate) class: Arc<Class>,
     pub(crate) state: FieldVector,
 }
 
 impl Entity {
-    pub(crate) fn new(index: u32, serial: u32, class: Rc<Class>, state: FieldVector) -> Self {
+    pub(crate) fn new(index: u32, serial: u32, class: Arc<Class>, state: FieldVector) -> Self {
         Entity {
             index,
             serial,
@@ -102,16 +314,120 @@ impl Entity {
         self.get_property_by_field_path(&self.class.serializer.get_field_path_for_name(name)?)
     }
 
-    pub(crate) fn get_property_by_field_path(&self, fp: &FieldPath) -> Result<&FieldValue> {
-        self.state.get_value(fp).with_context(|| {
-            anyhow!(
-                "No property for given name \"{}\" ({}, {:?})",
-                self.class.serializer.get_name_for_field_path(fp),
-                self.class().name(),
-                fp
+    /// Elements of array or vector property, e.g. `m_hItems`. Length is taken
+    /// from the current state.
+    pub fn get_array<'b, T>(&'b self, name: &str) -> Result<Vec<T>>
+    where
+        &'b FieldValue: TryInto<T, Error = anyhow::Error>,
+    {
+        let len = self.array_len(name)?;
+        let mut fp = self.class.serializer.get_field_path_for_name(name)?;
+        fp.last += 1;
+        (0..len)
+            .map(|i| {
+                fp.path[fp.last] = i as u8;
+                self.get_property_by_field_path(&fp)?.try_into()
+            })
+            .collect()
+    }
+
+    pub(crate) fn array_len(&self, name: &str) -> Result<usize> {
+        let fp = self.class.serializer.get_field_path_for_name(name)?;
+        let Some(vector) = self.state.get_field_vector(&fp) else {
+            bail!(
+                "Property \"{}\" is not an array ({})",
+                name,
+                self.class().name()
             )
+        };
+        Ok(vector.states().len())
+    }
+
+    /// All set properties with their names.
+    pub fn properties(&self) -> impl Iterator<Item = (String, &FieldValue)> {
+        self.class
+            .serializer
+            .get_field_paths(&mut FieldPath::new(), &self.state)
+            .into_iter()
+            .filter_map(move |fp| {
+                Some((
+                    self.class.serializer.get_name_for_field_path(&fp),
+                    self.state.get_value(&fp, &self.class.serializer)?,
+                ))
+            })
+    }
+
+    /// Properties with names matching `pattern`, where `*` matches any
+    /// sequence of characters, e.g. `m_vecDataTeam.*.m_iReliableGold`.
+    pub fn properties_matching<'a>(
+        &'a self,
+        pattern: &'a str,
+    ) -> impl Iterator<Item = (String, &'a FieldValue)> {
+        self.properties()
+            .filter(move |(name, _)| glob_matches(pattern, name))
+    }
+
+    /// World position of the entity. Combines cell and offset properties
+    /// either from `CBodyComponent` (heroes, units) or from entity itself
+    /// (some buildings and older replays). Returns `None` for entities without
+    /// position.
+    pub fn position(&self) -> Option<[f32; 3]> {
+        ["CBodyComponent.", ""].iter().find_map(|prefix| {
+            let coord = |axis: char| -> Option<f32> {
+                let cell: u32 = self
+                    .get_property_by_name(&format!("{prefix}m_cell{axis}"))
+                    .ok()?
+                    .try_into()
+                    .ok()?;
+                let vec: f32 = self
+                    .get_property_by_name(&format!("{prefix}m_vec{axis}"))
+                    .ok()?
+                    .try_into()
+                    .ok()?;
+                Some(cell as f32 * CELL_SIZE + vec - WORLD_OFFSET)
+            };
+            Some([coord('X')?, coord('Y')?, coord('Z')?])
         })
     }
+
+    /// Pitch, yaw and roll of the entity in degrees.
+    pub fn rotation(&self) -> Option<[f32; 3]> {
+        ["CBodyComponent.m_angRotation", "m_angRotation"]
+            .iter()
+            .find_map(|name| self.get_property_by_name(name).ok()?.try_into().ok())
+    }
+
+    pub(crate) fn get_property_by_field_path(&self, fp: &FieldPath) -> Result<&FieldValue> {
+        self.state
+            .get_value(fp, &self.class.serializer)
+            .with_context(|| {
+                anyhow!(
+                    "No property for given name \"{}\" ({}, {:?})",
+                    self.class.serializer.get_name_for_field_path(fp),
+                    self.class().name(),
+                    fp
+                )
+            })
+    }
+}
+
+/// Matches name against a pattern where `*` stands for any sequence of
+/// characters, as used by [`Entity::properties_matching`].
+pub fn glob_matches(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*').collect::<Vec<_>>();
+    let Some(mut rest) = name.strip_prefix(parts.remove(0)) else {
+        return false;
+    };
+    let Some(last) = parts.pop() else {
+        return rest.is_empty();
+    };
+    for part in parts {
+        let Some(idx) = rest.find(part) else {
+            return false;
+        };
+        rest = &rest[idx + part.len()..];
+    }
+    rest.ends_with(last)
 }
 
 impl Display for Entities {
@@ -143,7 +459,7 @@ impl Display for Entity {
         {
             let field_type = self.class.serializer.get_type_for_field_path(&fp);
             let name = self.class.serializer.get_name_for_field_path(&fp);
-            let value = self.state.get_value(&fp);
+            let value = self.state.get_value(&fp, &self.class.serializer);
             if let Some(v) = value {
                 table.add_row(row![fp, name, field_type.as_string(), format!("{:?}", v)]);
             } else {
@@ -154,3 +470,31 @@ impl Display for Entity {
         write!(f, "{}", table)
     }
 }
+
+/// Serialized as a map of entity index to entity.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Entities {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+        let entities: Vec<_> = self.iter().collect();
+        let mut map = serializer.serialize_map(Some(entities.len()))?;
+        for entity in entities {
+            map.serialize_entry(&entity.index(), entity)?;
+        }
+        map.end()
+    }
+}
+
+/// Serialized as a map of property name to value.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Entity {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+        let properties: Vec<_> = self.properties().collect();
+        let mut map = serializer.serialize_map(Some(properties.len()))?;
+        for (name, value) in properties {
+            map.serialize_entry(&name, value)?;
+        }
+        map.end()
+    }
+}
@@ -1,15 +1,17 @@
 use crate::decoder::Decoder;
 use crate::field_value::FieldValue;
+use crate::reader::Reader;
 use crate::serializer::Serializer;
 use lazy_static::lazy_static;
 use regex::Regex;
-use std::fmt::{Display, Formatter};
-use std::rc::Rc;
+use std::fmt::{Debug, Display, Formatter};
+use std::sync::{Arc, OnceLock};
 
 pub(crate) struct Field {
     pub(crate) var_name: Box<str>,
-    pub(crate) field_type: Rc<FieldType>,
+    pub(crate) field_type: Arc<FieldType>,
     pub(crate) model: FieldModel,
+    pub(crate) properties: FieldProperties,
 
     pub(crate) decoder: Decoder,
 }
@@ -24,7 +26,7 @@ impl Field {
             FieldModel::FixedArray | FieldModel::VariableArray(_) => {
                 if let Some(s) = st.get_field_vector(fp) {
                     fp.last += 1;
-                    for (i, _) in s.state.iter().enumerate() {
+                    for (i, _) in s.states().iter().enumerate() {
                         fp.path[fp.last] = i as u8;
                         vec.push(*fp);
                     }
@@ -41,7 +43,7 @@ impl Field {
             FieldModel::VariableTable(serializer) => {
                 if let Some(x) = st.get_field_vector(fp) {
                     fp.last += 2;
-                    for (i, v) in x.state.iter().enumerate() {
+                    for (i, v) in x.states().iter().enumerate() {
                         if let StateType::Vector(_) = v {
                             fp.path[fp.last - 1] = i as u8;
                             vec.extend(serializer.get_field_paths(fp, st));
@@ -55,7 +57,7 @@ impl Field {
     }
 }
 
-#[derive(Clone, Copy, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Encoder {
     Coord,
     SimTime,
@@ -67,7 +69,7 @@ pub enum Encoder {
 
 impl Encoder {
     #[inline(always)]
-    pub fn from_str(s: &str) -> Option<Self> {
+    pub(crate) fn from_str(s: &str) -> Option<Self> {
         match s {
             "coord" => Some(Encoder::Coord),
             "simtime" => Some(Encoder::SimTime),
@@ -80,7 +82,7 @@ impl Encoder {
     }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug)]
 pub struct FieldProperties {
     pub encoder: Option<Encoder>,
     pub encoder_flags: i32,
@@ -93,13 +95,14 @@ pub enum FieldModel {
     Simple,
     FixedArray,
     VariableArray(Decoder),
-    FixedTable(Rc<Serializer>),
-    VariableTable(Rc<Serializer>),
+    FixedTable(Arc<Serializer>),
+    VariableTable(Arc<Serializer>),
 }
 
 #[derive(Clone, Debug)]
 pub enum StateType {
     Value(FieldValue),
+    Lazy(LazyValue),
     Vector(FieldVector),
 }
 
@@ -112,40 +115,96 @@ impl StateType {
             None
         }
     }
+}
 
-    pub fn as_value(&self) -> Option<&FieldValue> {
-        if let StateType::Value(x) = self {
-            Some(x)
-        } else {
-            None
+/// Value stored as a bit offset into the entity data of the packet it was
+/// read from, see [`crate::Parser::enable_lazy_decoding`]. Decoded on the
+/// first read with the decoder of its field path.
+#[derive(Clone)]
+pub struct LazyValue {
+    data: Arc<Vec<u8>>,
+    offset: usize,
+    value: OnceLock<FieldValue>,
+}
+
+impl LazyValue {
+    pub(crate) fn new(data: Arc<Vec<u8>>, offset: usize) -> Self {
+        LazyValue {
+            data,
+            offset,
+            value: OnceLock::new(),
+        }
+    }
+
+    pub(crate) fn get(&self, decoder: &Decoder) -> &FieldValue {
+        self.value.get_or_init(|| {
+            let mut reader = Reader::new(&self.data[self.offset / 8..]);
+            let skipped = (self.offset % 8) as u32;
+            if skipped > 0 {
+                reader.read_bits(skipped);
+            }
+            reader.refill();
+            decoder.decode(&mut reader)
+        })
+    }
+}
+
+impl Debug for LazyValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.value.get() {
+            Some(value) => Debug::fmt(value, f),
+            None => write!(f, "Lazy({})", self.offset),
         }
     }
 }
 
-#[derive(Clone, Debug)]
+/// Tree of field states. Nodes are shared between clones and copied on
+/// write, so cloning entity state or the whole [`crate::Entities`] only bumps
+/// reference counts. The first write after a clone copies only the nodes on
+/// the path to the value, other nodes stay shared. Empty nodes don't
+/// allocate.
+#[derive(Clone, Debug, Default)]
 pub struct FieldVector {
-    pub(crate) state: Vec<StateType>,
+    state: Option<Arc<Vec<StateType>>>,
 }
 
 impl FieldVector {
     #[inline(always)]
     pub fn new() -> Self {
-        FieldVector { state: vec![] }
+        FieldVector { state: None }
     }
 
+    /// True if both vectors share the same state, i.e. one is an unchanged
+    /// clone of the other.
+    pub(crate) fn ptr_eq(&self, other: &FieldVector) -> bool {
+        match (&self.state, &other.state) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn states(&self) -> &[StateType] {
+        self.state.as_deref().map_or(&[], |x| x.as_slice())
+    }
+
+    /// Value at the field path, undecoded values are decoded with the decoder
+    /// from `serializer`.
     #[inline(always)]
-    pub fn get_value(&self, fp: &FieldPath) -> Option<&FieldValue> {
+    pub fn get_value(&self, fp: &FieldPath, serializer: &Serializer) -> Option<&FieldValue> {
         let mut current_state = self;
         for i in 0..fp.last {
             current_state = current_state
-                .state
+                .states()
                 .get(fp.path[i] as usize)?
                 .as_field_vector()?;
         }
-        current_state
-            .state
-            .get(fp.path[fp.last] as usize)?
-            .as_value()
+        match current_state.states().get(fp.path[fp.last] as usize)? {
+            StateType::Value(x) => Some(x),
+            StateType::Lazy(x) => Some(x.get(serializer.get_decoder_for_field_path(fp))),
+            StateType::Vector(_) => None,
+        }
     }
 
     #[inline(always)]
@@ -153,40 +212,46 @@ impl FieldVector {
         let mut current_state = self;
         for i in 0..fp.last {
             current_state = current_state
-                .state
+                .states()
                 .get(fp.path[i] as usize)?
                 .as_field_vector()?;
         }
         current_state
-            .state
+            .states()
             .get(fp.path[fp.last] as usize)?
             .as_field_vector()
     }
 
     #[inline(always)]
     pub fn set(&mut self, fp: &FieldPath, v: FieldValue) {
+        self.set_state(fp, StateType::Value(v))
+    }
+
+    #[inline(always)]
+    pub(crate) fn set_lazy(&mut self, fp: &FieldPath, v: LazyValue) {
+        self.set_state(fp, StateType::Lazy(v))
+    }
+
+    #[inline(always)]
+    fn set_state(&mut self, fp: &FieldPath, v: StateType) {
         let mut current_state = self;
         for i in 0..=fp.last {
             let index = fp.path[i] as usize;
-            if current_state.state.len() <= index {
-                current_state
-                    .state
-                    .resize_with(index + 1, || StateType::Vector(FieldVector::new()))
+            let state = Arc::make_mut(current_state.state.get_or_insert_with(Arc::default));
+            if state.len() <= index {
+                state.resize_with(index + 1, || StateType::Vector(FieldVector::new()))
             }
 
             if i == fp.last {
-                current_state.state[index] = StateType::Value(v);
+                state[index] = v;
                 return;
             }
 
-            match &mut current_state.state[index] {
-                StateType::Value(_) => {
-                    current_state.state[index] = StateType::Vector(FieldVector::new());
-                }
-                StateType::Vector(_) => {}
+            if !matches!(state[index], StateType::Vector(_)) {
+                state[index] = StateType::Vector(FieldVector::new());
             }
 
-            match &mut current_state.state[index] {
+            match &mut state[index] {
                 StateType::Vector(x) => {
                     current_state = x;
                 }
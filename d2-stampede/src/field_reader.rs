@@ -1,33 +1,72 @@
-use crate::field::{FieldPath, FieldVector};
+use crate::field::{FieldPath, FieldVector, LazyValue};
 use crate::reader::Reader;
 use crate::serializer::Serializer;
-use std::cell::RefCell;
+use lazy_static::lazy_static;
 use std::cmp::Ordering;
 use std::collections::BinaryHeap;
+use std::sync::Arc;
+
+lazy_static! {
+    static ref HUFFMAN_TREE: HTree =
+        build_huffman_tree(OPERATIONS.map(|(_, weight)| weight).into());
+}
 
 pub(crate) struct FieldReader {
-    tree: HTree,
-    paths_buf: RefCell<[FieldPath; 4096]>,
+    tree: &'static HTree,
+    paths_buf: [FieldPath; 4096],
 }
 
 impl FieldReader {
     pub(crate) fn new() -> Self {
-        let tree = build_huffman_tree(OPERATIONS.map(|(_, weight)| weight).into());
-        let paths = RefCell::new([FieldPath::new(); 4096]);
+        let paths = [FieldPath::new(); 4096];
         FieldReader {
-            tree,
+            tree: &HUFFMAN_TREE,
             paths_buf: paths,
         }
     }
 
     pub(crate) fn read_fields(
-        &self,
+        &mut self,
+        reader: &mut Reader,
+        serializer: &Serializer,
+        state: &mut FieldVector,
+    ) {
+        let n = self.read_paths(reader);
+        self.paths_buf[..n]
+            .iter_mut()
+            .for_each(|fp| state.set(fp, serializer.get_decoder_for_field_path(fp).decode(reader)))
+    }
+
+    /// Moves reader past the fields without building values or updating the
+    /// state.
+    /// Stores positions of the values in `data` instead of decoding them.
+    /// Values are still skipped with their decoders to find where the next
+    /// one starts. `reader` must read `data` from the start.
+    pub(crate) fn read_fields_lazy(
+        &mut self,
         reader: &mut Reader,
         serializer: &Serializer,
         state: &mut FieldVector,
+        data: &Arc<Vec<u8>>,
     ) {
-        let mut paths = self.paths_buf.borrow_mut();
-        let mut node = &self.tree;
+        let n = self.read_paths(reader);
+        self.paths_buf[..n].iter().for_each(|fp| {
+            let offset = reader.bit_position();
+            serializer.get_decoder_for_field_path(fp).skip(reader);
+            state.set_lazy(fp, LazyValue::new(data.clone(), offset));
+        })
+    }
+
+    pub(crate) fn skip_fields(&mut self, reader: &mut Reader, serializer: &Serializer) {
+        let n = self.read_paths(reader);
+        self.paths_buf[..n]
+            .iter()
+            .for_each(|fp| serializer.get_decoder_for_field_path(fp).skip(reader))
+    }
+
+    fn read_paths(&mut self, reader: &mut Reader) -> usize {
+        let paths = &mut self.paths_buf;
+        let mut node = self.tree;
         let mut i = 0;
         let mut fp = FieldPath::new();
         reader.refill();
@@ -45,7 +84,7 @@ impl FieldReader {
                     }
                     paths[i] = fp;
                     i += 1;
-                    node = &self.tree;
+                    node = self.tree;
                     reader.refill();
                 }
                 HTree::Node { .. } => {
@@ -53,10 +92,7 @@ impl FieldReader {
                 }
             }
         }
-
-        paths[..i]
-            .iter_mut()
-            .for_each(|fp| state.set(fp, serializer.get_decoder_for_field_path(fp).decode(reader)))
+        i
     }
 }
 
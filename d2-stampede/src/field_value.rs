@@ -1,3 +1,4 @@
+use crate::entity::EHandle;
 use anyhow::{anyhow, bail, format_err};
 use std::fmt::{Display, Formatter};
 
@@ -10,6 +11,7 @@ pub enum FieldValue {
     Vector2D([f32; 2]),
     Vector3D([f32; 3]),
     Vector4D([f32; 4]),
+    QAngle([f32; 3]),
 
     Signed8(i8),
     Signed16(i16),
@@ -29,7 +31,9 @@ impl Display for FieldValue {
             FieldValue::String(val) => write!(f, "\"{}\"", val),
             FieldValue::Float(val) => write!(f, "{}", val),
             FieldValue::Vector2D(val) => write!(f, "[{}, {}]", val[0], val[1]),
-            FieldValue::Vector3D(val) => write!(f, "[{}, {}, {}]", val[0], val[1], val[2]),
+            FieldValue::Vector3D(val) | FieldValue::QAngle(val) => {
+                write!(f, "[{}, {}, {}]", val[0], val[1], val[2])
+            }
             FieldValue::Vector4D(val) => {
                 write!(f, "[{}, {}, {}, {}]", val[0], val[1], val[2], val[3])
             }
@@ -45,6 +49,30 @@ impl Display for FieldValue {
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for FieldValue {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            FieldValue::Boolean(val) => serializer.serialize_bool(*val),
+            FieldValue::String(val) => serializer.serialize_str(val),
+            FieldValue::Float(val) => serializer.serialize_f32(*val),
+            FieldValue::Vector2D(val) => serde::Serialize::serialize(val, serializer),
+            FieldValue::Vector3D(val) | FieldValue::QAngle(val) => {
+                serde::Serialize::serialize(val, serializer)
+            }
+            FieldValue::Vector4D(val) => serde::Serialize::serialize(val, serializer),
+            FieldValue::Signed8(val) => serializer.serialize_i8(*val),
+            FieldValue::Signed16(val) => serializer.serialize_i16(*val),
+            FieldValue::Signed32(val) => serializer.serialize_i32(*val),
+            FieldValue::Signed64(val) => serializer.serialize_i64(*val),
+            FieldValue::Unsigned8(val) => serializer.serialize_u8(*val),
+            FieldValue::Unsigned16(val) => serializer.serialize_u16(*val),
+            FieldValue::Unsigned32(val) => serializer.serialize_u32(*val),
+            FieldValue::Unsigned64(val) => serializer.serialize_u64(*val),
+        }
+    }
+}
+
 impl TryInto<String> for FieldValue {
     type Error = anyhow::Error;
 
@@ -121,7 +149,7 @@ impl TryInto<[f32; 3]> for FieldValue {
     type Error = anyhow::Error;
 
     fn try_into(self) -> anyhow::Result<[f32; 3], anyhow::Error> {
-        if let FieldValue::Vector3D(x) = self {
+        if let FieldValue::Vector3D(x) | FieldValue::QAngle(x) = self {
             Ok(x)
         } else {
             bail!("Error converting \"{}\" into [f32; 3]", self)
@@ -133,7 +161,7 @@ impl TryInto<[f32; 3]> for &FieldValue {
     type Error = anyhow::Error;
 
     fn try_into(self) -> anyhow::Result<[f32; 3], anyhow::Error> {
-        if let FieldValue::Vector3D(x) = self {
+        if let FieldValue::Vector3D(x) | FieldValue::QAngle(x) = self {
             Ok(*x)
         } else {
             bail!("Error converting \"{}\" into [f32; 3]", self)
@@ -145,7 +173,7 @@ impl TryInto<(f32, f32, f32)> for FieldValue {
     type Error = anyhow::Error;
 
     fn try_into(self) -> anyhow::Result<(f32, f32, f32), anyhow::Error> {
-        if let FieldValue::Vector3D(x) = self {
+        if let FieldValue::Vector3D(x) | FieldValue::QAngle(x) = self {
             Ok(x.into())
         } else {
             bail!("Error converting \"{}\" into (f32, f32, f32)", self)
@@ -157,7 +185,7 @@ impl TryInto<(f32, f32, f32)> for &FieldValue {
     type Error = anyhow::Error;
 
     fn try_into(self) -> anyhow::Result<(f32, f32, f32), anyhow::Error> {
-        if let FieldValue::Vector3D(x) = self {
+        if let FieldValue::Vector3D(x) | FieldValue::QAngle(x) = self {
             Ok((*x).into())
         } else {
             bail!("Error converting \"{}\" into (f32, f32, f32)", self)
@@ -219,7 +247,7 @@ impl TryInto<Vec<f32>> for FieldValue {
     fn try_into(self) -> anyhow::Result<Vec<f32>, anyhow::Error> {
         match self {
             FieldValue::Vector2D(x) => Ok(x.to_vec()),
-            FieldValue::Vector3D(x) => Ok(x.to_vec()),
+            FieldValue::Vector3D(x) | FieldValue::QAngle(x) => Ok(x.to_vec()),
             FieldValue::Vector4D(x) => Ok(x.to_vec()),
             _ => bail!("Error converting \"{}\" into Vec<f32>", self),
         }
@@ -232,7 +260,7 @@ impl TryInto<Vec<f32>> for &FieldValue {
     fn try_into(self) -> anyhow::Result<Vec<f32>, anyhow::Error> {
         match self {
             FieldValue::Vector2D(x) => Ok(x.to_vec()),
-            FieldValue::Vector3D(x) => Ok(x.to_vec()),
+            FieldValue::Vector3D(x) | FieldValue::QAngle(x) => Ok(x.to_vec()),
             FieldValue::Vector4D(x) => Ok(x.to_vec()),
             _ => bail!("Error converting \"{}\" into Vec<f32>", self),
         }
@@ -287,6 +315,88 @@ impl TryInto<bool> for &FieldValue {
     }
 }
 
+#[cfg(feature = "glam")]
+impl TryInto<glam::Vec2> for FieldValue {
+    type Error = anyhow::Error;
+
+    fn try_into(self) -> anyhow::Result<glam::Vec2, anyhow::Error> {
+        (&self).try_into()
+    }
+}
+
+#[cfg(feature = "glam")]
+impl TryInto<glam::Vec2> for &FieldValue {
+    type Error = anyhow::Error;
+
+    fn try_into(self) -> anyhow::Result<glam::Vec2, anyhow::Error> {
+        if let FieldValue::Vector2D(x) = self {
+            Ok(glam::Vec2::from_array(*x))
+        } else {
+            bail!("Error converting \"{}\" into Vec2", self)
+        }
+    }
+}
+
+#[cfg(feature = "glam")]
+impl TryInto<glam::Vec3> for FieldValue {
+    type Error = anyhow::Error;
+
+    fn try_into(self) -> anyhow::Result<glam::Vec3, anyhow::Error> {
+        (&self).try_into()
+    }
+}
+
+#[cfg(feature = "glam")]
+impl TryInto<glam::Vec3> for &FieldValue {
+    type Error = anyhow::Error;
+
+    fn try_into(self) -> anyhow::Result<glam::Vec3, anyhow::Error> {
+        if let FieldValue::Vector3D(x) | FieldValue::QAngle(x) = self {
+            Ok(glam::Vec3::from_array(*x))
+        } else {
+            bail!("Error converting \"{}\" into Vec3", self)
+        }
+    }
+}
+
+#[cfg(feature = "glam")]
+impl TryInto<glam::Vec4> for FieldValue {
+    type Error = anyhow::Error;
+
+    fn try_into(self) -> anyhow::Result<glam::Vec4, anyhow::Error> {
+        (&self).try_into()
+    }
+}
+
+#[cfg(feature = "glam")]
+impl TryInto<glam::Vec4> for &FieldValue {
+    type Error = anyhow::Error;
+
+    fn try_into(self) -> anyhow::Result<glam::Vec4, anyhow::Error> {
+        if let FieldValue::Vector4D(x) = self {
+            Ok(glam::Vec4::from_array(*x))
+        } else {
+            bail!("Error converting \"{}\" into Vec4", self)
+        }
+    }
+}
+
+impl TryInto<EHandle> for FieldValue {
+    type Error = anyhow::Error;
+
+    fn try_into(self) -> anyhow::Result<EHandle, anyhow::Error> {
+        self.as_handle()
+    }
+}
+
+impl TryInto<EHandle> for &FieldValue {
+    type Error = anyhow::Error;
+
+    fn try_into(self) -> anyhow::Result<EHandle, anyhow::Error> {
+        self.as_handle()
+    }
+}
+
 macro_rules! impl_try_into_for_integers {
     ($target:ty) => {
         impl TryInto<$target> for FieldValue {
@@ -459,6 +569,15 @@ impl FieldValue {
         }
     }
 
+    #[inline]
+    pub(crate) fn as_qangle(&self) -> &[f32; 3] {
+        if let FieldValue::QAngle(v) = self {
+            v
+        } else {
+            panic!("Tried to read as QAngle, Found {:?}", self);
+        }
+    }
+
     #[inline]
     pub(crate) fn as_vector4d(&self) -> &[f32; 4] {
         if let FieldValue::Vector4D(v) = self {
@@ -468,3 +587,13 @@ impl FieldValue {
         }
     }
 }
+
+impl FieldValue {
+    /// Interprets value as entity handle. Handles are decoded as `u32`.
+    pub fn as_handle(&self) -> anyhow::Result<EHandle> {
+        match self {
+            FieldValue::Unsigned32(x) => Ok(EHandle::new(*x)),
+            _ => bail!("Error converting \"{}\" into EHandle", self),
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use crate::combat_log::{CombatLog, CombatLogTypes};
+use crate::entity::{Entity, EntityEvents};
+use crate::game_event::GameEvent;
+use crate::parser::{Context, Observer};
+use anyhow::Result;
+
+type ContextFn<'a> = Box<dyn FnMut(&Context) -> Result<()> + 'a>;
+type EntityFn<'a> = Box<dyn FnMut(&Context, EntityEvents, &Entity) -> Result<()> + 'a>;
+type CombatLogFn<'a> = Box<dyn FnMut(&Context, &CombatLog) -> Result<()> + 'a>;
+type GameEventFn<'a> = Box<dyn FnMut(&Context, &GameEvent) -> Result<()> + 'a>;
+
+/// Observer calling closures registered with [`crate::Parser::on_tick_start`]
+/// and similar methods. Returned reference can be passed to
+/// [`crate::Parser::remove_observer`] or [`crate::Parser::pause_observer`].
+#[derive(Default)]
+pub struct FnObserver<'a> {
+    pub(crate) tick_start: Option<ContextFn<'a>>,
+    pub(crate) tick_end: Option<ContextFn<'a>>,
+    pub(crate) entity: Option<EntityFn<'a>>,
+    pub(crate) combat_log: Option<(CombatLogTypes, CombatLogFn<'a>)>,
+    pub(crate) game_event: Option<GameEventFn<'a>>,
+    pub(crate) interval: Option<(u32, ContextFn<'a>)>,
+}
+
+impl Observer for FnObserver<'_> {
+    fn on_tick_start(&mut self, ctx: &Context) -> Result<()> {
+        self.tick_start.as_mut().map_or(Ok(()), |f| f(ctx))
+    }
+
+    fn on_tick_end(&mut self, ctx: &Context) -> Result<()> {
+        self.tick_end.as_mut().map_or(Ok(()), |f| f(ctx))
+    }
+
+    fn on_entity(&mut self, ctx: &Context, event: EntityEvents, entity: &Entity) -> Result<()> {
+        self.entity
+            .as_mut()
+            .map_or(Ok(()), |f| f(ctx, event, entity))
+    }
+
+    fn on_combat_log(&mut self, ctx: &Context, combat_log: &CombatLog) -> Result<()> {
+        self.combat_log
+            .as_mut()
+            .map_or(Ok(()), |(_, f)| f(ctx, combat_log))
+    }
+
+    fn combat_log_types(&self) -> CombatLogTypes {
+        self.combat_log
+            .as_ref()
+            .map_or(CombatLogTypes::NONE, |(types, _)| *types)
+    }
+
+    fn on_game_event(&mut self, ctx: &Context, event: &GameEvent) -> Result<()> {
+        self.game_event.as_mut().map_or(Ok(()), |f| f(ctx, event))
+    }
+
+    fn on_interval(&mut self, ctx: &Context) -> Result<()> {
+        self.interval.as_mut().map_or(Ok(()), |(_, f)| f(ctx))
+    }
+
+    fn interval(&self) -> Option<u32> {
+        self.interval.as_ref().map(|(interval, _)| *interval)
+    }
+}
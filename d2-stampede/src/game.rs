@@ -0,0 +1,130 @@
+use crate::combat_log::{CombatLog, CombatLogTypes};
+use crate::dota::DotaMessages;
+use crate::parser::{Context, Observer};
+use anyhow::Result;
+use hashbrown::HashSet;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Source 2 game the replay was recorded in. Entities, string tables, game
+/// events and base messages are parsed the same way for every game, user
+/// messages and combat log are handled by [`GameMessages`] of the game. User
+/// messages of games without one are passed to
+/// [`Observer::on_unknown_message`] and can be decoded with protobufs of
+/// that game.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub enum Game {
+    #[default]
+    Unknown,
+    Dota,
+    Deadlock,
+    Cs2,
+}
+
+impl Game {
+    /// Detects the game from the game dir of `CSVCMsg_ServerInfo` or
+    /// `CDemoFileHeader`, e.g. `/opt/srcds/dota/dota_v6123/`.
+    pub fn from_game_dir(game_dir: &str) -> Self {
+        let game_dir = game_dir.to_lowercase();
+        if game_dir.contains("dota") {
+            Game::Dota
+        } else if game_dir.contains("citadel") {
+            Game::Deadlock
+        } else if game_dir.contains("csgo") || game_dir.contains("cs2") {
+            Game::Cs2
+        } else {
+            Game::Unknown
+        }
+    }
+    /// Message set of the game, [`Source2Messages`] for games without one.
+    pub fn messages(self) -> Box<dyn GameMessages> {
+        match self {
+            Game::Dota => Box::new(DotaMessages::default()),
+            _ => Box::new(Source2Messages),
+        }
+    }
+}
+
+/// Serializers decoded as pointers in every Source 2 game.
+const SOURCE2_POINTER_TYPES: &[&str] = &[
+    "PhysicsRagdollPose_t",
+    "CBodyComponent",
+    "CEntityIdentity",
+    "CPhysicsComponent",
+    "CRenderComponent",
+    "CPlayerLocalData",
+    "CPlayer_CameraServices",
+];
+
+/// Game specific layer on top of Source 2 messages. Parser takes it from
+/// [`Game::messages`] of the replay, another set can be plugged with
+/// [`crate::Parser::set_game_messages`].
+#[allow(unused_variables)]
+pub trait GameMessages {
+    /// Serializers of the game decoded as pointers, in addition to the ones
+    /// shared by all Source 2 games.
+    fn pointer_types(&self) -> &[&str] {
+        &[]
+    }
+
+    /// Name of inner message with given id if it belongs to the set, used in
+    /// [`crate::ParseReport`]. Other ids are handled as base Source 2
+    /// messages or passed to [`Observer::on_unknown_message`]. Game specific
+    /// ids overlap between games, so they are checked first.
+    fn message_name(&self, msg_type: i32) -> Option<String> {
+        None
+    }
+
+    /// Called for inner messages [`GameMessages::message_name`] returned a
+    /// name for.
+    fn on_message(&mut self, dispatch: &Dispatch, msg_type: i32, msg: &[u8]) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called at the end of every tick before [`Observer::on_tick_end`].
+    fn on_tick_end(&mut self, dispatch: &Dispatch) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Parser state passed to [`GameMessages`].
+pub struct Dispatch<'p, 'a> {
+    pub(crate) context: &'p Context,
+    pub(crate) observers: &'p [Rc<RefCell<dyn Observer + 'a>>],
+    pub(crate) combat_log_types: &'p [CombatLogTypes],
+    pub(crate) combat_log_mask: CombatLogTypes,
+}
+
+impl<'a> Dispatch<'_, 'a> {
+    pub fn context(&self) -> &Context {
+        self.context
+    }
+
+    /// Observers that are not paused, in the order of delivery.
+    pub fn observers(&self) -> &[Rc<RefCell<dyn Observer + 'a>>] {
+        self.observers
+    }
+
+    pub(crate) fn notify_combat_log(&self, entry: &CombatLog) -> Result<()> {
+        let type_ = entry.type_();
+        self.observers
+            .iter()
+            .zip(self.combat_log_types.iter())
+            .filter(|(_, types)| types.contains(type_))
+            .try_for_each(|(obs, _)| obs.borrow_mut().on_combat_log(self.context, entry))
+    }
+}
+
+/// Base Source 2 messages only, game specific user messages are passed to
+/// [`Observer::on_unknown_message`].
+pub struct Source2Messages;
+
+impl GameMessages for Source2Messages {}
+
+pub(crate) fn pointer_types(messages: &dyn GameMessages) -> HashSet<&str> {
+    SOURCE2_POINTER_TYPES
+        .iter()
+        .chain(messages.pointer_types())
+        .copied()
+        .collect()
+}
@@ -0,0 +1,119 @@
+use crate::field_value::FieldValue;
+use crate::proto::{
+    c_msg_source1_legacy_game_event, CMsgSource1LegacyGameEvent, CMsgSource1LegacyGameEventList,
+};
+use anyhow::{anyhow, bail, Context, Result};
+use hashbrown::HashMap;
+use prettytable::{row, Table};
+use std::fmt::{Display, Formatter};
+
+pub(crate) struct GameEventDescriptor {
+    pub(crate) name: Box<str>,
+    pub(crate) keys: Vec<Box<str>>,
+}
+
+pub(crate) struct GameEventList {
+    pub(crate) descriptors: HashMap<i32, GameEventDescriptor>,
+}
+
+impl GameEventList {
+    pub(crate) fn new() -> Self {
+        GameEventList {
+            descriptors: HashMap::default(),
+        }
+    }
+
+    pub(crate) fn update(&mut self, list: CMsgSource1LegacyGameEventList) {
+        for descriptor in list.descriptors {
+            self.descriptors.insert(
+                descriptor.eventid(),
+                GameEventDescriptor {
+                    name: descriptor.name().into(),
+                    keys: descriptor
+                        .keys
+                        .iter()
+                        .map(|key| key.name().into())
+                        .collect(),
+                },
+            );
+        }
+    }
+
+    pub(crate) fn decode_event(&self, event: CMsgSource1LegacyGameEvent) -> Result<GameEvent<'_>> {
+        let id = event.eventid();
+        let descriptor = self
+            .descriptors
+            .get(&id)
+            .with_context(|| anyhow!("No game event descriptor for id {}", id))?;
+
+        let values = event
+            .keys
+            .into_iter()
+            .map(Self::decode_value)
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(GameEvent {
+            id,
+            descriptor,
+            values,
+        })
+    }
+
+    fn decode_value(key: c_msg_source1_legacy_game_event::KeyT) -> Result<FieldValue> {
+        let value = match key.r#type() {
+            1 => FieldValue::String(key.val_string.unwrap_or_default()),
+            2 => FieldValue::Float(key.val_float()),
+            3 | 8 => FieldValue::Signed32(key.val_long()),
+            4 | 9 => FieldValue::Signed16(key.val_short() as i16),
+            5 => FieldValue::Unsigned8(key.val_byte() as u8),
+            6 => FieldValue::Boolean(key.val_bool()),
+            7 => FieldValue::Unsigned64(key.val_uint64()),
+            x => bail!("Unknown game event key type {}", x),
+        };
+        Ok(value)
+    }
+}
+
+/// Decoded `CMsgSource1LegacyGameEvent` with keys resolved from event
+/// descriptors.
+pub struct GameEvent<'a> {
+    id: i32,
+    descriptor: &'a GameEventDescriptor,
+    values: Vec<FieldValue>,
+}
+
+impl<'a> GameEvent<'a> {
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.descriptor.name
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &FieldValue)> {
+        self.descriptor
+            .keys
+            .iter()
+            .map(|key| key.as_ref())
+            .zip(self.values.iter())
+    }
+
+    pub fn get_value(&self, key: &str) -> Result<&FieldValue> {
+        self.iter()
+            .find(|(name, _)| *name == key)
+            .map(|(_, value)| value)
+            .with_context(|| anyhow!("No key \"{}\" for game event \"{}\"", key, self.name()))
+    }
+}
+
+impl Display for GameEvent<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut table = Table::new();
+        table.add_row(row!["key", "value"]);
+        for (key, value) in self.iter() {
+            table.add_row(row![key, value]);
+        }
+        write!(f, "{}\n{}", self.name(), table)
+    }
+}
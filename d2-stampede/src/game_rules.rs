@@ -0,0 +1,98 @@
+use crate::entity::Entity;
+use crate::field_value::FieldValue;
+use crate::parser::Context;
+use crate::proto::DotaGameState;
+use anyhow::Result;
+
+/// Dota 2 server runs at 30 ticks per second.
+pub(crate) const TICKS_PER_SECOND: f32 = 30.0;
+
+/// Typed accessors for `CDOTAGamerulesProxy` entity.
+pub struct GameRules<'a> {
+    ctx: &'a Context,
+    entity: &'a Entity,
+}
+
+impl<'a> GameRules<'a> {
+    pub(crate) fn new(ctx: &'a Context, entity: &'a Entity) -> Self {
+        GameRules { ctx, entity }
+    }
+
+    pub fn entity(&self) -> &'a Entity {
+        self.entity
+    }
+
+    pub fn game_state(&self) -> Result<DotaGameState> {
+        let state: i32 = self.property("m_nGameState")?;
+        Ok(DotaGameState::try_from(state)?)
+    }
+
+    pub fn game_mode(&self) -> Result<i32> {
+        self.property("m_iGameMode")
+    }
+
+    pub fn is_paused(&self) -> Result<bool> {
+        self.property("m_bGamePaused")
+    }
+
+    /// Server time of the pre-game start (hero selection is over).
+    pub fn pre_game_start_time(&self) -> Result<f32> {
+        self.property("m_flPreGameStartTime")
+    }
+
+    /// Server time of the horn, `0.0` until game is in progress.
+    pub fn game_start_time(&self) -> Result<f32> {
+        self.property("m_flGameStartTime")
+    }
+
+    /// Server time of the end of the current game state, e.g. the horn during
+    /// pre-game.
+    pub fn state_transition_time(&self) -> Result<f32> {
+        self.property("m_flStateTransitionTime")
+    }
+
+    /// Server time in seconds with pauses excluded.
+    pub fn server_time(&self) -> Result<f32> {
+        let tick: i32 = if self.is_paused()? {
+            self.property("m_nPauseStartTick")?
+        } else {
+            self.ctx.net_tick() as i32
+        };
+        let paused_ticks: i32 = self.property("m_nTotalPausedTicks")?;
+        Ok((tick - paused_ticks) as f32 / TICKS_PER_SECOND)
+    }
+
+    /// In-game clock time in seconds. Negative before the horn.
+    pub fn game_time(&self) -> Result<f32> {
+        let start_time = match self.game_start_time()? {
+            x if x > 0.0 => x,
+            _ => self.state_transition_time()?,
+        };
+        Ok(self.server_time()? - start_time)
+    }
+
+    /// Time of day in range `0.0..1.0`, where `0.25` is the start of the day
+    /// and `0.75` is the start of the night.
+    pub fn time_of_day(&self) -> Result<f32> {
+        let time: i32 = self.property("m_iNetTimeOfDay")?;
+        Ok(time as f32 / 65536.0)
+    }
+
+    /// Night by the day cycle or forced by abilities like Night Stalker's Dark
+    /// Ascension.
+    pub fn is_night(&self) -> Result<bool> {
+        let time_of_day = self.time_of_day()?;
+        let forced: bool = self.property("m_bIsNightstalkerNight")?;
+        let temporary: bool = self.property("m_bIsTemporaryNight")?;
+        Ok(!(0.25..0.75).contains(&time_of_day) || forced || temporary)
+    }
+
+    fn property<T>(&self, name: &str) -> Result<T>
+    where
+        &'a FieldValue: TryInto<T, Error = anyhow::Error>,
+    {
+        self.entity
+            .get_property_by_name(&format!("m_pGameRules.{name}"))?
+            .try_into()
+    }
+}
@@ -1,14 +1,34 @@
 mod class;
+mod clock;
+#[cfg(feature = "arrow")]
+pub mod columnar;
 mod combat_log;
+#[cfg(feature = "compression")]
+mod compression;
 mod decoder;
+mod dota;
 mod entity;
 mod field;
 mod field_reader;
 mod field_value;
+mod fn_observer;
+mod game;
+mod game_event;
+mod game_rules;
+#[cfg(feature = "names")]
+mod names;
 mod parser;
+mod player_resource;
+mod prepared;
+mod progress;
 mod reader;
+mod report;
+mod schema_cache;
 mod serializer;
+mod snapshot;
 mod string_table;
+mod user_messages;
+mod writer;
 
 #[macro_export]
 macro_rules! try_observers {
@@ -29,6 +49,16 @@ macro_rules! property {
     }};
 }
 
+#[macro_export]
+macro_rules! property_array {
+    ($ent:expr, $fmt:expr, $($arg:tt)*) => {
+        $ent.get_array(&format!($fmt, $($arg)*))?
+    };
+    ($ent:expr, $fmt:expr) => {{
+        $ent.get_array(&format!($fmt))?
+    }};
+}
+
 #[macro_export]
 macro_rules! try_property {
     ($ent:expr, $fmt:expr, $($arg:tt)*) => {
@@ -51,31 +81,77 @@ macro_rules! try_property {
 }
 
 pub mod prelude {
-    pub use crate::{property, try_property};
+    pub use crate::{property, property_array, try_property};
+
+    pub use crate::parser::{Context, Observer, ObserverHandle, Parser};
 
-    pub use crate::parser::{Context, Observer, Parser};
+    pub use crate::user_messages::{LazyMessage, UserMessageObserver};
 
-    pub use crate::entity::{Entity, EntityEvents};
+    pub use crate::entity::{EHandle, Entity, EntityEvents};
 
-    pub use crate::combat_log::CombatLog;
+    pub use crate::combat_log::{CombatLog, CombatLogEntry, CombatLogTypes};
+
+    pub use crate::string_table::StringTable;
+
+    pub use crate::game_event::GameEvent;
+
+    pub use crate::game_rules::GameRules;
+
+    pub use crate::player_resource::PlayerResource;
 
     pub use crate::field_value::FieldValue;
 
     pub use d2_stampede_protobufs::prost::Message;
 }
 
-pub use crate::parser::{Context, Observer, Parser};
+pub use crate::parser::{Context, Observer, ObserverHandle, Parser};
+
+pub use crate::user_messages::{LazyMessage, UserMessageObserver};
+
+pub use crate::fn_observer::FnObserver;
+
+pub use crate::entity::{glob_matches, EHandle, Entities, Entity, EntityEvents, EntityQuery};
 
-pub use crate::entity::{Entities, Entity, EntityEvents};
+pub use crate::class::{Class, Classes, FieldInfo, FieldKind};
 
-pub use crate::class::{Class, Classes};
+pub use crate::clock::ClockMap;
+
+pub use crate::field::{Encoder, FieldProperties};
+
+pub use crate::decoder::{DecoderReader, DecoderRegistry, FieldDecoder};
+
+pub use crate::schema_cache::SchemaCache;
+
+pub use crate::snapshot::{EntityChange, Snapshot, SnapshotDiff};
 
 pub use crate::string_table::{StringTable, StringTableEntry, StringTables};
 
-pub use crate::combat_log::CombatLog;
+pub use crate::combat_log::{CombatLog, CombatLogEntry, CombatLogTypes};
+
+pub use crate::dota::DotaMessages;
+
+pub use crate::game::{Dispatch, Game, GameMessages, Source2Messages};
+
+pub use crate::game_event::GameEvent;
+
+pub use crate::game_rules::GameRules;
+
+pub use crate::player_resource::PlayerResource;
 
 pub use crate::field_value::FieldValue;
 
+pub use crate::progress::{CancelToken, Cancelled, Progress};
+
+pub use crate::report::{ParseReport, SkippedMessage};
+
+#[cfg(feature = "compression")]
+pub use crate::compression::{decompress, read_replay};
+
+#[cfg(feature = "names")]
+pub use crate::names::{HeroName, Names, HEROES};
+
+pub use crate::writer::{trim_replay, DemoWriter, RawMessage, RawMessages};
+
 pub use anyhow::Error;
 
 pub use anyhow::Result;
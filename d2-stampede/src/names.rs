@@ -0,0 +1,679 @@
+use hashbrown::HashMap;
+use lazy_static::lazy_static;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct HeroName {
+    pub id: i32,
+    /// Unit name, same as in combat log, e.g. `npc_dota_hero_nevermore`.
+    pub name: &'static str,
+    /// English name, e.g. `Shadow Fiend`.
+    pub localized_name: &'static str,
+}
+
+/// Hero names by id, ids are the same as `m_nSelectedHeroID` and
+/// `hero_id` in draft and game events.
+pub const HEROES: &[HeroName] = &[
+    HeroName {
+        id: 1,
+        name: "npc_dota_hero_antimage",
+        localized_name: "Anti-Mage",
+    },
+    HeroName {
+        id: 2,
+        name: "npc_dota_hero_axe",
+        localized_name: "Axe",
+    },
+    HeroName {
+        id: 3,
+        name: "npc_dota_hero_bane",
+        localized_name: "Bane",
+    },
+    HeroName {
+        id: 4,
+        name: "npc_dota_hero_bloodseeker",
+        localized_name: "Bloodseeker",
+    },
+    HeroName {
+        id: 5,
+        name: "npc_dota_hero_crystal_maiden",
+        localized_name: "Crystal Maiden",
+    },
+    HeroName {
+        id: 6,
+        name: "npc_dota_hero_drow_ranger",
+        localized_name: "Drow Ranger",
+    },
+    HeroName {
+        id: 7,
+        name: "npc_dota_hero_earthshaker",
+        localized_name: "Earthshaker",
+    },
+    HeroName {
+        id: 8,
+        name: "npc_dota_hero_juggernaut",
+        localized_name: "Juggernaut",
+    },
+    HeroName {
+        id: 9,
+        name: "npc_dota_hero_mirana",
+        localized_name: "Mirana",
+    },
+    HeroName {
+        id: 10,
+        name: "npc_dota_hero_morphling",
+        localized_name: "Morphling",
+    },
+    HeroName {
+        id: 11,
+        name: "npc_dota_hero_nevermore",
+        localized_name: "Shadow Fiend",
+    },
+    HeroName {
+        id: 12,
+        name: "npc_dota_hero_phantom_lancer",
+        localized_name: "Phantom Lancer",
+    },
+    HeroName {
+        id: 13,
+        name: "npc_dota_hero_puck",
+        localized_name: "Puck",
+    },
+    HeroName {
+        id: 14,
+        name: "npc_dota_hero_pudge",
+        localized_name: "Pudge",
+    },
+    HeroName {
+        id: 15,
+        name: "npc_dota_hero_razor",
+        localized_name: "Razor",
+    },
+    HeroName {
+        id: 16,
+        name: "npc_dota_hero_sand_king",
+        localized_name: "Sand King",
+    },
+    HeroName {
+        id: 17,
+        name: "npc_dota_hero_storm_spirit",
+        localized_name: "Storm Spirit",
+    },
+    HeroName {
+        id: 18,
+        name: "npc_dota_hero_sven",
+        localized_name: "Sven",
+    },
+    HeroName {
+        id: 19,
+        name: "npc_dota_hero_tiny",
+        localized_name: "Tiny",
+    },
+    HeroName {
+        id: 20,
+        name: "npc_dota_hero_vengefulspirit",
+        localized_name: "Vengeful Spirit",
+    },
+    HeroName {
+        id: 21,
+        name: "npc_dota_hero_windrunner",
+        localized_name: "Windranger",
+    },
+    HeroName {
+        id: 22,
+        name: "npc_dota_hero_zuus",
+        localized_name: "Zeus",
+    },
+    HeroName {
+        id: 23,
+        name: "npc_dota_hero_kunkka",
+        localized_name: "Kunkka",
+    },
+    HeroName {
+        id: 25,
+        name: "npc_dota_hero_lina",
+        localized_name: "Lina",
+    },
+    HeroName {
+        id: 26,
+        name: "npc_dota_hero_lion",
+        localized_name: "Lion",
+    },
+    HeroName {
+        id: 27,
+        name: "npc_dota_hero_shadow_shaman",
+        localized_name: "Shadow Shaman",
+    },
+    HeroName {
+        id: 28,
+        name: "npc_dota_hero_slardar",
+        localized_name: "Slardar",
+    },
+    HeroName {
+        id: 29,
+        name: "npc_dota_hero_tidehunter",
+        localized_name: "Tidehunter",
+    },
+    HeroName {
+        id: 30,
+        name: "npc_dota_hero_witch_doctor",
+        localized_name: "Witch Doctor",
+    },
+    HeroName {
+        id: 31,
+        name: "npc_dota_hero_lich",
+        localized_name: "Lich",
+    },
+    HeroName {
+        id: 32,
+        name: "npc_dota_hero_riki",
+        localized_name: "Riki",
+    },
+    HeroName {
+        id: 33,
+        name: "npc_dota_hero_enigma",
+        localized_name: "Enigma",
+    },
+    HeroName {
+        id: 34,
+        name: "npc_dota_hero_tinker",
+        localized_name: "Tinker",
+    },
+    HeroName {
+        id: 35,
+        name: "npc_dota_hero_sniper",
+        localized_name: "Sniper",
+    },
+    HeroName {
+        id: 36,
+        name: "npc_dota_hero_necrolyte",
+        localized_name: "Necrophos",
+    },
+    HeroName {
+        id: 37,
+        name: "npc_dota_hero_warlock",
+        localized_name: "Warlock",
+    },
+    HeroName {
+        id: 38,
+        name: "npc_dota_hero_beastmaster",
+        localized_name: "Beastmaster",
+    },
+    HeroName {
+        id: 39,
+        name: "npc_dota_hero_queenofpain",
+        localized_name: "Queen of Pain",
+    },
+    HeroName {
+        id: 40,
+        name: "npc_dota_hero_venomancer",
+        localized_name: "Venomancer",
+    },
+    HeroName {
+        id: 41,
+        name: "npc_dota_hero_faceless_void",
+        localized_name: "Faceless Void",
+    },
+    HeroName {
+        id: 42,
+        name: "npc_dota_hero_skeleton_king",
+        localized_name: "Wraith King",
+    },
+    HeroName {
+        id: 43,
+        name: "npc_dota_hero_death_prophet",
+        localized_name: "Death Prophet",
+    },
+    HeroName {
+        id: 44,
+        name: "npc_dota_hero_phantom_assassin",
+        localized_name: "Phantom Assassin",
+    },
+    HeroName {
+        id: 45,
+        name: "npc_dota_hero_pugna",
+        localized_name: "Pugna",
+    },
+    HeroName {
+        id: 46,
+        name: "npc_dota_hero_templar_assassin",
+        localized_name: "Templar Assassin",
+    },
+    HeroName {
+        id: 47,
+        name: "npc_dota_hero_viper",
+        localized_name: "Viper",
+    },
+    HeroName {
+        id: 48,
+        name: "npc_dota_hero_luna",
+        localized_name: "Luna",
+    },
+    HeroName {
+        id: 49,
+        name: "npc_dota_hero_dragon_knight",
+        localized_name: "Dragon Knight",
+    },
+    HeroName {
+        id: 50,
+        name: "npc_dota_hero_dazzle",
+        localized_name: "Dazzle",
+    },
+    HeroName {
+        id: 51,
+        name: "npc_dota_hero_rattletrap",
+        localized_name: "Clockwerk",
+    },
+    HeroName {
+        id: 52,
+        name: "npc_dota_hero_leshrac",
+        localized_name: "Leshrac",
+    },
+    HeroName {
+        id: 53,
+        name: "npc_dota_hero_furion",
+        localized_name: "Nature's Prophet",
+    },
+    HeroName {
+        id: 54,
+        name: "npc_dota_hero_life_stealer",
+        localized_name: "Lifestealer",
+    },
+    HeroName {
+        id: 55,
+        name: "npc_dota_hero_dark_seer",
+        localized_name: "Dark Seer",
+    },
+    HeroName {
+        id: 56,
+        name: "npc_dota_hero_clinkz",
+        localized_name: "Clinkz",
+    },
+    HeroName {
+        id: 57,
+        name: "npc_dota_hero_omniknight",
+        localized_name: "Omniknight",
+    },
+    HeroName {
+        id: 58,
+        name: "npc_dota_hero_enchantress",
+        localized_name: "Enchantress",
+    },
+    HeroName {
+        id: 59,
+        name: "npc_dota_hero_huskar",
+        localized_name: "Huskar",
+    },
+    HeroName {
+        id: 60,
+        name: "npc_dota_hero_night_stalker",
+        localized_name: "Night Stalker",
+    },
+    HeroName {
+        id: 61,
+        name: "npc_dota_hero_broodmother",
+        localized_name: "Broodmother",
+    },
+    HeroName {
+        id: 62,
+        name: "npc_dota_hero_bounty_hunter",
+        localized_name: "Bounty Hunter",
+    },
+    HeroName {
+        id: 63,
+        name: "npc_dota_hero_weaver",
+        localized_name: "Weaver",
+    },
+    HeroName {
+        id: 64,
+        name: "npc_dota_hero_jakiro",
+        localized_name: "Jakiro",
+    },
+    HeroName {
+        id: 65,
+        name: "npc_dota_hero_batrider",
+        localized_name: "Batrider",
+    },
+    HeroName {
+        id: 66,
+        name: "npc_dota_hero_chen",
+        localized_name: "Chen",
+    },
+    HeroName {
+        id: 67,
+        name: "npc_dota_hero_spectre",
+        localized_name: "Spectre",
+    },
+    HeroName {
+        id: 68,
+        name: "npc_dota_hero_ancient_apparition",
+        localized_name: "Ancient Apparition",
+    },
+    HeroName {
+        id: 69,
+        name: "npc_dota_hero_doom_bringer",
+        localized_name: "Doom",
+    },
+    HeroName {
+        id: 70,
+        name: "npc_dota_hero_ursa",
+        localized_name: "Ursa",
+    },
+    HeroName {
+        id: 71,
+        name: "npc_dota_hero_spirit_breaker",
+        localized_name: "Spirit Breaker",
+    },
+    HeroName {
+        id: 72,
+        name: "npc_dota_hero_gyrocopter",
+        localized_name: "Gyrocopter",
+    },
+    HeroName {
+        id: 73,
+        name: "npc_dota_hero_alchemist",
+        localized_name: "Alchemist",
+    },
+    HeroName {
+        id: 74,
+        name: "npc_dota_hero_invoker",
+        localized_name: "Invoker",
+    },
+    HeroName {
+        id: 75,
+        name: "npc_dota_hero_silencer",
+        localized_name: "Silencer",
+    },
+    HeroName {
+        id: 76,
+        name: "npc_dota_hero_obsidian_destroyer",
+        localized_name: "Outworld Destroyer",
+    },
+    HeroName {
+        id: 77,
+        name: "npc_dota_hero_lycan",
+        localized_name: "Lycan",
+    },
+    HeroName {
+        id: 78,
+        name: "npc_dota_hero_brewmaster",
+        localized_name: "Brewmaster",
+    },
+    HeroName {
+        id: 79,
+        name: "npc_dota_hero_shadow_demon",
+        localized_name: "Shadow Demon",
+    },
+    HeroName {
+        id: 80,
+        name: "npc_dota_hero_lone_druid",
+        localized_name: "Lone Druid",
+    },
+    HeroName {
+        id: 81,
+        name: "npc_dota_hero_chaos_knight",
+        localized_name: "Chaos Knight",
+    },
+    HeroName {
+        id: 82,
+        name: "npc_dota_hero_meepo",
+        localized_name: "Meepo",
+    },
+    HeroName {
+        id: 83,
+        name: "npc_dota_hero_treant",
+        localized_name: "Treant Protector",
+    },
+    HeroName {
+        id: 84,
+        name: "npc_dota_hero_ogre_magi",
+        localized_name: "Ogre Magi",
+    },
+    HeroName {
+        id: 85,
+        name: "npc_dota_hero_undying",
+        localized_name: "Undying",
+    },
+    HeroName {
+        id: 86,
+        name: "npc_dota_hero_rubick",
+        localized_name: "Rubick",
+    },
+    HeroName {
+        id: 87,
+        name: "npc_dota_hero_disruptor",
+        localized_name: "Disruptor",
+    },
+    HeroName {
+        id: 88,
+        name: "npc_dota_hero_nyx_assassin",
+        localized_name: "Nyx Assassin",
+    },
+    HeroName {
+        id: 89,
+        name: "npc_dota_hero_naga_siren",
+        localized_name: "Naga Siren",
+    },
+    HeroName {
+        id: 90,
+        name: "npc_dota_hero_keeper_of_the_light",
+        localized_name: "Keeper of the Light",
+    },
+    HeroName {
+        id: 91,
+        name: "npc_dota_hero_wisp",
+        localized_name: "Io",
+    },
+    HeroName {
+        id: 92,
+        name: "npc_dota_hero_visage",
+        localized_name: "Visage",
+    },
+    HeroName {
+        id: 93,
+        name: "npc_dota_hero_slark",
+        localized_name: "Slark",
+    },
+    HeroName {
+        id: 94,
+        name: "npc_dota_hero_medusa",
+        localized_name: "Medusa",
+    },
+    HeroName {
+        id: 95,
+        name: "npc_dota_hero_troll_warlord",
+        localized_name: "Troll Warlord",
+    },
+    HeroName {
+        id: 96,
+        name: "npc_dota_hero_centaur",
+        localized_name: "Centaur Warrunner",
+    },
+    HeroName {
+        id: 97,
+        name: "npc_dota_hero_magnataur",
+        localized_name: "Magnus",
+    },
+    HeroName {
+        id: 98,
+        name: "npc_dota_hero_shredder",
+        localized_name: "Timbersaw",
+    },
+    HeroName {
+        id: 99,
+        name: "npc_dota_hero_bristleback",
+        localized_name: "Bristleback",
+    },
+    HeroName {
+        id: 100,
+        name: "npc_dota_hero_tusk",
+        localized_name: "Tusk",
+    },
+    HeroName {
+        id: 101,
+        name: "npc_dota_hero_skywrath_mage",
+        localized_name: "Skywrath Mage",
+    },
+    HeroName {
+        id: 102,
+        name: "npc_dota_hero_abaddon",
+        localized_name: "Abaddon",
+    },
+    HeroName {
+        id: 103,
+        name: "npc_dota_hero_elder_titan",
+        localized_name: "Elder Titan",
+    },
+    HeroName {
+        id: 104,
+        name: "npc_dota_hero_legion_commander",
+        localized_name: "Legion Commander",
+    },
+    HeroName {
+        id: 105,
+        name: "npc_dota_hero_techies",
+        localized_name: "Techies",
+    },
+    HeroName {
+        id: 106,
+        name: "npc_dota_hero_ember_spirit",
+        localized_name: "Ember Spirit",
+    },
+    HeroName {
+        id: 107,
+        name: "npc_dota_hero_earth_spirit",
+        localized_name: "Earth Spirit",
+    },
+    HeroName {
+        id: 108,
+        name: "npc_dota_hero_abyssal_underlord",
+        localized_name: "Underlord",
+    },
+    HeroName {
+        id: 109,
+        name: "npc_dota_hero_terrorblade",
+        localized_name: "Terrorblade",
+    },
+    HeroName {
+        id: 110,
+        name: "npc_dota_hero_phoenix",
+        localized_name: "Phoenix",
+    },
+    HeroName {
+        id: 111,
+        name: "npc_dota_hero_oracle",
+        localized_name: "Oracle",
+    },
+    HeroName {
+        id: 112,
+        name: "npc_dota_hero_winter_wyvern",
+        localized_name: "Winter Wyvern",
+    },
+    HeroName {
+        id: 113,
+        name: "npc_dota_hero_arc_warden",
+        localized_name: "Arc Warden",
+    },
+    HeroName {
+        id: 114,
+        name: "npc_dota_hero_monkey_king",
+        localized_name: "Monkey King",
+    },
+    HeroName {
+        id: 119,
+        name: "npc_dota_hero_dark_willow",
+        localized_name: "Dark Willow",
+    },
+    HeroName {
+        id: 120,
+        name: "npc_dota_hero_pangolier",
+        localized_name: "Pangolier",
+    },
+    HeroName {
+        id: 121,
+        name: "npc_dota_hero_grimstroke",
+        localized_name: "Grimstroke",
+    },
+    HeroName {
+        id: 123,
+        name: "npc_dota_hero_hoodwink",
+        localized_name: "Hoodwink",
+    },
+    HeroName {
+        id: 126,
+        name: "npc_dota_hero_void_spirit",
+        localized_name: "Void Spirit",
+    },
+    HeroName {
+        id: 128,
+        name: "npc_dota_hero_snapfire",
+        localized_name: "Snapfire",
+    },
+    HeroName {
+        id: 129,
+        name: "npc_dota_hero_mars",
+        localized_name: "Mars",
+    },
+    HeroName {
+        id: 131,
+        name: "npc_dota_hero_ringmaster",
+        localized_name: "Ringmaster",
+    },
+    HeroName {
+        id: 135,
+        name: "npc_dota_hero_dawnbreaker",
+        localized_name: "Dawnbreaker",
+    },
+    HeroName {
+        id: 136,
+        name: "npc_dota_hero_marci",
+        localized_name: "Marci",
+    },
+    HeroName {
+        id: 137,
+        name: "npc_dota_hero_primal_beast",
+        localized_name: "Primal Beast",
+    },
+    HeroName {
+        id: 138,
+        name: "npc_dota_hero_muerta",
+        localized_name: "Muerta",
+    },
+    HeroName {
+        id: 145,
+        name: "npc_dota_hero_kez",
+        localized_name: "Kez",
+    },
+    HeroName {
+        id: 155,
+        name: "npc_dota_hero_largo",
+        localized_name: "Largo",
+    },
+];
+
+lazy_static! {
+    static ref BY_ID: HashMap<i32, &'static HeroName> =
+        HEROES.iter().map(|hero| (hero.id, hero)).collect();
+    static ref BY_NAME: HashMap<&'static str, &'static HeroName> =
+        HEROES.iter().map(|hero| (hero.name, hero)).collect();
+}
+
+/// Lookups in the embedded hero table. Item and ability ids are not
+/// included, they change with every patch and can't be taken from the
+/// replay.
+pub struct Names;
+
+impl Names {
+    pub fn hero(id: i32) -> Option<&'static HeroName> {
+        BY_ID.get(&id).copied()
+    }
+
+    /// Hero by unit or combat log name, e.g. `npc_dota_hero_axe`.
+    pub fn hero_by_name(name: &str) -> Option<&'static HeroName> {
+        BY_NAME.get(name).copied()
+    }
+
+    /// English name for hero unit name, other names are returned as is.
+    pub fn localized(name: &str) -> &str {
+        Self::hero_by_name(name).map_or(name, |hero| hero.localized_name)
+    }
+}
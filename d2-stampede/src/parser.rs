@@ -1,58 +1,158 @@
 use crate::class::{Class, Classes};
-use crate::combat_log::CombatLog;
-use crate::decoder::Decoder;
+use crate::combat_log::{CombatLog, CombatLogTypes};
+use crate::decoder::{Decoder, DecoderRegistry};
 use crate::entity::{Entities, Entity, EntityEvents};
 use crate::field::{Encoder, Field, FieldModel, FieldProperties, FieldType, FieldVector};
 use crate::field_reader::FieldReader;
+use crate::fn_observer::FnObserver;
+use crate::game::{pointer_types, Dispatch, Game, GameMessages};
+use crate::game_event::{GameEvent, GameEventList};
+use crate::game_rules::GameRules;
+use crate::player_resource::PlayerResource;
+use crate::prepared::{PacketMessages, Prepared, SendTables};
+use crate::progress::{CancelToken, Cancelled, Progress};
 use crate::proto::*;
 use crate::reader::Reader;
+use crate::report::ParseReport;
+use crate::schema_cache::{Digest, SchemaCache, Serializers};
 use crate::serializer::Serializer;
 use crate::string_table::{StringTable, StringTableEntry, StringTables};
 use crate::try_observers;
+use crate::user_messages::{dispatch_base_user_message, UserMessageObserver};
+use crate::writer::RawMessages;
 use anyhow::{bail, Result};
 use hashbrown::{HashMap, HashSet};
 use prettytable::{row, Table};
 use regex::Regex;
-use std::cell::RefCell;
-use std::collections::VecDeque;
-use std::fmt::{Display, Formatter};
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::fmt::{Debug, Display, Formatter};
 use std::mem;
+use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::rc::Rc;
+use std::sync::Arc;
+
+struct RegisteredObserver<'a> {
+    observer: Rc<RefCell<dyn Observer + 'a>>,
+    priority: i32,
+    paused: bool,
+    next_interval_tick: u32,
+    handle: ObserverHandle,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ObserverRequest {
+    Pause,
+    Resume,
+    Remove,
+}
+
+/// Pauses, resumes or removes an observer from inside callbacks, where the
+/// parser is borrowed. Requests are applied after the current outer message,
+/// the last one wins. See [`Parser::observer_handle`] and
+/// [`Parser::set_observer_handle`].
+#[derive(Debug, Clone, Default)]
+pub struct ObserverHandle(Rc<Cell<Option<ObserverRequest>>>);
+
+impl ObserverHandle {
+    /// Handle that is not bound to any observer yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn pause(&self) {
+        self.0.set(Some(ObserverRequest::Pause));
+    }
+
+    pub fn resume(&self) {
+        self.0.set(Some(ObserverRequest::Resume));
+    }
 
+    pub fn remove(&self) {
+        self.0.set(Some(ObserverRequest::Remove));
+    }
+}
+
+/// Parser is not [`Send`], registered observers are shared with the caller
+/// through [`Rc`] and progress callback may capture non-`Send` state. Parse
+/// each replay on the thread it is created on and send the results instead:
+/// [`Context`], [`Entities`], [`Entity`], [`Class`] and
+/// [`Snapshot`](crate::Snapshot) are `Send + Sync`.
 pub struct Parser<'a> {
     reader: Reader<'a>,
     field_reader: FieldReader,
+    registered: Vec<RegisteredObserver<'a>>,
+    /// Observers that are not paused, in the order of delivery.
     observers: Vec<Rc<RefCell<dyn Observer + 'a>>>,
+    /// Combat log types of active observers, in the same order.
+    combat_log_types: Vec<CombatLogTypes>,
+    /// Union of all combat log types, other entries are not decoded.
+    combat_log_mask: CombatLogTypes,
+    decoded_classes: Option<HashSet<Box<str>>>,
+    lazy_decoding: bool,
     start_offset: usize,
 
-    combat_log: VecDeque<CMsgDotaCombatLogEntry>,
+    game_messages: Box<dyn GameMessages + 'a>,
+    custom_game_messages: bool,
 
     prologue_completed: bool,
     processing_deltas: bool,
 
+    recovery: bool,
+    report: ParseReport,
+
+    progress_callback: Option<Box<dyn FnMut(Progress) + 'a>>,
+    cancel_token: Option<CancelToken>,
+    schema_cache: Option<SchemaCache>,
+    /// Digest of send tables if they are in the schema cache.
+    schema_digest: Option<Digest>,
+    decoders: DecoderRegistry,
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    parallel_reading: bool,
+
     pub context: Context,
 }
 
+/// Raw `instancebaseline` entries and states decoded from them. States are
+/// decoded on the first entity creation of the class and shared with created
+/// entities until they are updated.
 pub(crate) struct Baselines {
     field_reader: FieldReader,
-    baselines: HashMap<i32, Rc<Vec<u8>>>,
+    baselines: HashMap<i32, Arc<Vec<u8>>>,
     states: HashMap<i32, FieldVector>,
+    cache: bool,
 }
 
 impl Baselines {
-    pub(crate) fn add_baseline(&mut self, id: i32, baseline: Rc<Vec<u8>>) {
+    /// Decoded state is dropped only if the baseline has changed, full
+    /// packets resend the same baselines.
+    pub(crate) fn add_baseline(&mut self, id: i32, baseline: Arc<Vec<u8>>) {
+        if self
+            .baselines
+            .get(&id)
+            .is_some_and(|old| Arc::ptr_eq(old, &baseline) || old == &baseline)
+        {
+            return;
+        }
+        self.states.remove(&id);
         self.baselines.insert(id, baseline);
     }
 
-    pub(crate) fn read_baseline(&mut self, class: &Class) {
+    pub(crate) fn get_state(&mut self, class: &Class) -> FieldVector {
+        if let Some(state) = self.states.get(&class.id) {
+            return state.clone();
+        }
+
         let mut state = FieldVector::new();
         self.field_reader.read_fields(
             &mut Reader::new(&self.baselines[&class.id]),
             &class.serializer,
             &mut state,
         );
-
-        self.states.insert(class.id, state);
+        if self.cache {
+            self.states.insert(class.id, state.clone());
+        }
+        state
     }
 }
 
@@ -60,15 +160,17 @@ pub struct Context {
     pub(crate) classes: Classes,
     pub(crate) entities: Entities,
     pub(crate) string_tables: StringTables,
+    pub(crate) game_events: GameEventList,
     pub(crate) replay_info: CDemoFileInfo,
 
     pub(crate) tick: u32,
 
     pub(crate) net_tick: u32,
+    pub(crate) game: Game,
     pub(crate) game_build: u32,
 
     baselines: Baselines,
-    serializers: HashMap<Box<str>, Rc<Serializer>>,
+    serializers: Arc<Serializers>,
     last_full_packet_tick: u32,
 }
 
@@ -85,6 +187,24 @@ impl Context {
         &self.string_tables
     }
 
+    /// Typed accessors for `CDOTAGamerulesProxy` entity.
+    pub fn game_rules(&self) -> Result<GameRules<'_>> {
+        let entity = self.entities.get_by_class_name("CDOTAGamerulesProxy")?;
+        Ok(GameRules::new(self, entity))
+    }
+
+    /// In-game clock time in seconds with pauses excluded, see
+    /// [`GameRules::game_time`].
+    pub fn game_time(&self) -> Result<f32> {
+        self.game_rules()?.game_time()
+    }
+
+    /// Typed accessors for `CDOTA_PlayerResource` entity.
+    pub fn player_resource(&self) -> Result<PlayerResource<'_>> {
+        let entity = self.entities.get_by_class_name("CDOTA_PlayerResource")?;
+        Ok(PlayerResource::new(entity))
+    }
+
     pub fn replay_info(&self) -> &CDemoFileInfo {
         &self.replay_info
     }
@@ -97,6 +217,11 @@ impl Context {
         self.net_tick
     }
 
+    /// Game detected from the file header and the server info.
+    pub fn game(&self) -> Game {
+        self.game
+    }
+
     pub fn game_build(&self) -> u32 {
         self.game_build
     }
@@ -110,16 +235,25 @@ impl Display for Context {
         table.add_row(row!["String Tables", self.string_tables.tables.len()]);
         table.add_row(row!["Tick", self.tick]);
         table.add_row(row!["Net Tick", self.net_tick]);
+        table.add_row(row!["Game", format!("{:?}", self.game)]);
         table.add_row(row!["Game Build", format!("{:?}", self.game_build)]);
         write!(f, "{}", table)
     }
 }
 
-struct OuterMessage {
-    msg_type: EDemoCommands,
+#[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+const PIPELINE_DEPTH: usize = 64;
+
+struct OuterMessage<'a> {
+    /// `None` for command ids missing from [`EDemoCommands`], see `cmd`.
+    msg_type: Option<EDemoCommands>,
+    cmd: i32,
     size: usize,
     tick: u32,
-    buf: Vec<u8>,
+    /// Borrowed from the replay unless the message is compressed.
+    buf: Cow<'a, [u8]>,
+    /// Payload decoded on the reading thread.
+    prepared: Option<Prepared>,
 }
 
 impl<'a> Parser<'a> {
@@ -128,6 +262,7 @@ impl<'a> Parser<'a> {
             field_reader: FieldReader::new(),
             baselines: HashMap::default(),
             states: HashMap::default(),
+            cache: true,
         };
 
         let mut reader = Reader::new(replay);
@@ -142,21 +277,40 @@ impl<'a> Parser<'a> {
 
         reader.read_bytes(8);
 
-        let replay_info = Self::replay_info(&mut reader)?;
+        let replay_info = Self::file_info(replay)?;
+        let game = Self::header(replay)
+            .map(|header| Game::from_game_dir(header.game_directory()))
+            .unwrap_or_default();
 
         Ok(Parser {
             reader,
             field_reader: FieldReader::new(),
+            registered: Vec::new(),
             observers: Vec::new(),
-            combat_log: VecDeque::new(),
+            combat_log_types: Vec::new(),
+            combat_log_mask: CombatLogTypes::NONE,
+            decoded_classes: None,
+            lazy_decoding: false,
+            game_messages: game.messages(),
+            custom_game_messages: false,
             prologue_completed: false,
             start_offset: 0,
             processing_deltas: true,
+            recovery: false,
+            report: ParseReport::default(),
+            progress_callback: None,
+            cancel_token: None,
+            schema_cache: None,
+            schema_digest: None,
+            decoders: DecoderRegistry::default(),
+            #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+            parallel_reading: true,
 
             context: Context {
                 classes: Classes::new(),
                 entities: Entities::new(),
                 string_tables: StringTables::new(),
+                game_events: GameEventList::new(),
 
                 replay_info,
 
@@ -164,10 +318,11 @@ impl<'a> Parser<'a> {
                 net_tick: u32::MAX,
                 last_full_packet_tick: u32::MAX,
 
+                game,
                 game_build: 0,
 
                 baselines,
-                serializers: HashMap::default(),
+                serializers: Arc::default(),
             },
         })
     }
@@ -179,19 +334,344 @@ impl<'a> Parser<'a> {
         T: Observer + Default + 'a,
     {
         let rc = Rc::new(RefCell::new(T::default()));
-        self.observers.push(rc.clone());
-        rc.clone()
+        self.push_observer(rc.clone());
+        rc
+    }
+
+    /// Calls `f` at the start of every tick, shortcut for an observer with
+    /// [`Observer::on_tick_start`].
+    pub fn on_tick_start(
+        &mut self,
+        f: impl FnMut(&Context) -> Result<()> + 'a,
+    ) -> Rc<RefCell<FnObserver<'a>>> {
+        self.register_fn(FnObserver {
+            tick_start: Some(Box::new(f)),
+            ..Default::default()
+        })
+    }
+
+    pub fn on_tick_end(
+        &mut self,
+        f: impl FnMut(&Context) -> Result<()> + 'a,
+    ) -> Rc<RefCell<FnObserver<'a>>> {
+        self.register_fn(FnObserver {
+            tick_end: Some(Box::new(f)),
+            ..Default::default()
+        })
+    }
+
+    /// Calls `f` for entity events of entities matching `filter`.
+    pub fn on_entity(
+        &mut self,
+        filter: impl Fn(&Entity) -> bool + 'a,
+        mut f: impl FnMut(&Context, EntityEvents, &Entity) -> Result<()> + 'a,
+    ) -> Rc<RefCell<FnObserver<'a>>> {
+        self.register_fn(FnObserver {
+            entity: Some(Box::new(
+                move |ctx: &Context, event: EntityEvents, entity: &Entity| {
+                    if filter(entity) {
+                        f(ctx, event, entity)
+                    } else {
+                        Ok(())
+                    }
+                },
+            )),
+            ..Default::default()
+        })
+    }
+
+    pub fn on_combat_log(
+        &mut self,
+        f: impl FnMut(&Context, &CombatLog) -> Result<()> + 'a,
+    ) -> Rc<RefCell<FnObserver<'a>>> {
+        self.on_combat_log_type(CombatLogTypes::ALL, f)
+    }
+
+    /// Calls `f` only for combat log entries of given types, other entries
+    /// are not decoded unless some other observer needs them.
+    pub fn on_combat_log_type(
+        &mut self,
+        types: impl Into<CombatLogTypes>,
+        f: impl FnMut(&Context, &CombatLog) -> Result<()> + 'a,
+    ) -> Rc<RefCell<FnObserver<'a>>> {
+        self.register_fn(FnObserver {
+            combat_log: Some((types.into(), Box::new(f))),
+            ..Default::default()
+        })
+    }
+
+    pub fn on_game_event(
+        &mut self,
+        f: impl FnMut(&Context, &GameEvent) -> Result<()> + 'a,
+    ) -> Rc<RefCell<FnObserver<'a>>> {
+        self.register_fn(FnObserver {
+            game_event: Some(Box::new(f)),
+            ..Default::default()
+        })
     }
 
-    fn replay_info(reader: &mut Reader) -> Result<CDemoFileInfo> {
-        let offset = u32::from_le_bytes(reader.buf[8..12].try_into()?) as usize;
-        if reader.buf.len() < offset {
-            bail!("Buf is too small")
+    /// Calls `f` every `interval` ticks.
+    pub fn on_interval(
+        &mut self,
+        interval: u32,
+        f: impl FnMut(&Context) -> Result<()> + 'a,
+    ) -> Rc<RefCell<FnObserver<'a>>> {
+        self.register_fn(FnObserver {
+            interval: Some((interval, Box::new(f))),
+            ..Default::default()
+        })
+    }
+
+    fn register_fn(&mut self, observer: FnObserver<'a>) -> Rc<RefCell<FnObserver<'a>>> {
+        let rc = Rc::new(RefCell::new(observer));
+        self.push_observer(rc.clone());
+        rc
+    }
+
+    fn push_observer(&mut self, observer: Rc<RefCell<dyn Observer + 'a>>) {
+        self.registered.push(RegisteredObserver {
+            observer,
+            priority: 0,
+            paused: false,
+            next_interval_tick: 0,
+            handle: ObserverHandle::new(),
+        });
+        self.update_observers();
+    }
+
+    /// Removes observer registered with [`Parser::register_observer`] or
+    /// returned by one of `on_*` methods. Returns false if the observer is not
+    /// registered. Use [`ObserverHandle`] to remove it from its own callback.
+    pub fn remove_observer<T: ?Sized>(&mut self, observer: &Rc<RefCell<T>>) -> bool {
+        let Some(idx) = self.find_observer(observer) else {
+            return false;
+        };
+        self.registered.remove(idx);
+        self.update_observers();
+        true
+    }
+
+    /// Sets the order of delivery. Observers with lower priority are called
+    /// first, observers with the same priority are called in the order of
+    /// registration. Default priority is `0`.
+    pub fn set_observer_priority<T: ?Sized>(
+        &mut self,
+        observer: &Rc<RefCell<T>>,
+        priority: i32,
+    ) -> Result<()> {
+        let Some(idx) = self.find_observer(observer) else {
+            bail!("Observer is not registered")
+        };
+        self.registered[idx].priority = priority;
+        self.update_observers();
+        Ok(())
+    }
+
+    /// Stops calling the observer until [`Parser::resume_observer`], e.g.
+    /// between [`Parser::run_to_tick`] calls.
+    pub fn pause_observer<T: ?Sized>(&mut self, observer: &Rc<RefCell<T>>) -> Result<()> {
+        self.set_paused(observer, true)
+    }
+
+    pub fn resume_observer<T: ?Sized>(&mut self, observer: &Rc<RefCell<T>>) -> Result<()> {
+        self.set_paused(observer, false)
+    }
+
+    /// Handle to pause or remove the observer from inside callbacks:
+    ///
+    /// ```ignore
+    /// let obs = parser.register_observer::<FirstBlood>();
+    /// obs.borrow_mut().handle = parser.observer_handle(&obs)?;
+    /// ```
+    pub fn observer_handle<T: ?Sized>(&self, observer: &Rc<RefCell<T>>) -> Result<ObserverHandle> {
+        let Some(idx) = self.find_observer(observer) else {
+            bail!("Observer is not registered")
+        };
+        Ok(self.registered[idx].handle.clone())
+    }
+
+    /// Binds a handle created before the observer, e.g. one moved into a
+    /// closure:
+    ///
+    /// ```ignore
+    /// let handle = ObserverHandle::new();
+    /// let inner = handle.clone();
+    /// let obs = parser.on_combat_log(move |_, combat_log| {
+    ///     if combat_log.type_() == DotaCombatlogTypes::DotaCombatlogFirstBlood {
+    ///         inner.remove();
+    ///     }
+    ///     Ok(())
+    /// });
+    /// parser.set_observer_handle(&obs, handle)?;
+    /// ```
+    pub fn set_observer_handle<T: ?Sized>(
+        &mut self,
+        observer: &Rc<RefCell<T>>,
+        handle: ObserverHandle,
+    ) -> Result<()> {
+        let Some(idx) = self.find_observer(observer) else {
+            bail!("Observer is not registered")
+        };
+        self.registered[idx].handle = handle;
+        Ok(())
+    }
+
+    fn apply_observer_requests(&mut self) {
+        let mut changed = false;
+        self.registered.retain_mut(|registered| {
+            let Some(request) = registered.handle.0.take() else {
+                return true;
+            };
+            changed = true;
+            match request {
+                ObserverRequest::Pause => registered.paused = true,
+                ObserverRequest::Resume => registered.paused = false,
+                ObserverRequest::Remove => return false,
+            }
+            true
+        });
+        if changed {
+            self.update_observers();
         }
-        let mut reader = Reader::new(&reader.buf[offset..]);
-        Ok(CDemoFileInfo::decode(
-            Self::read_message(&mut reader)?.unwrap().buf.as_slice(),
-        )?)
+    }
+
+    fn set_paused<T: ?Sized>(&mut self, observer: &Rc<RefCell<T>>, paused: bool) -> Result<()> {
+        let Some(idx) = self.find_observer(observer) else {
+            bail!("Observer is not registered")
+        };
+        self.registered[idx].paused = paused;
+        self.update_observers();
+        Ok(())
+    }
+
+    fn find_observer<T: ?Sized>(&self, observer: &Rc<RefCell<T>>) -> Option<usize> {
+        let ptr = Rc::as_ptr(observer) as *const ();
+        self.registered
+            .iter()
+            .position(|x| Rc::as_ptr(&x.observer) as *const () == ptr)
+    }
+
+    fn update_observers(&mut self) {
+        self.registered.sort_by_key(|x| x.priority);
+        self.observers = self
+            .registered
+            .iter()
+            .filter(|x| !x.paused)
+            .map(|x| x.observer.clone())
+            .collect();
+        self.combat_log_types = self
+            .observers
+            .iter()
+            .map(|x| x.borrow().combat_log_types())
+            .collect();
+        self.combat_log_mask = self
+            .combat_log_types
+            .iter()
+            .fold(CombatLogTypes::NONE, |acc, &x| acc.union(x));
+    }
+
+    /// Enables recovery mode. After `DEM_SyncTick`, inner and outer messages
+    /// that fail to decode or panic are skipped and parsing continues with
+    /// the next message. Errors returned by observers while the message is
+    /// processed are skipped the same way. Skipped messages are listed in
+    /// [`Parser::report`].
+    pub fn enable_recovery(&mut self) {
+        self.recovery = true;
+    }
+
+    /// Messages skipped in recovery mode.
+    pub fn report(&self) -> &ParseReport {
+        &self.report
+    }
+
+    /// Calls `f` after every outer message processed by
+    /// [`Parser::run_to_end`] and [`Parser::run_to_tick`].
+    pub fn set_progress_callback(&mut self, f: impl FnMut(Progress) + 'a) {
+        self.progress_callback = Some(Box::new(f));
+    }
+
+    /// Parser checks the token between outer messages and returns
+    /// [`Cancelled`] error once it is cancelled. Parser stays at the last
+    /// processed message, so it can be resumed later.
+    pub fn set_cancel_token(&mut self, token: CancelToken) {
+        self.cancel_token = Some(token);
+    }
+
+    /// Reuses serializers and classes from the cache if another parser
+    /// already decoded the same send tables, otherwise adds them to the cache.
+    /// Must be set before the prologue is parsed.
+    pub fn set_schema_cache(&mut self, cache: SchemaCache) {
+        self.schema_cache = Some(cache);
+    }
+
+    /// Custom decoders for unknown field encoders or types. Must be set
+    /// before the prologue is parsed. Schema cache is not used when custom
+    /// decoders are set.
+    pub fn set_decoders(&mut self, decoders: DecoderRegistry) {
+        self.decoders = decoders;
+    }
+
+    /// Replaces message set chosen by the game of the replay, e.g. to decode
+    /// user messages of a game without built-in set. Must be set before the
+    /// prologue is parsed. Schema cache is not used with custom message set,
+    /// its pointer types may change serializers.
+    pub fn set_game_messages(&mut self, messages: impl GameMessages + 'a) {
+        self.game_messages = Box::new(messages);
+        self.custom_game_messages = true;
+    }
+
+    /// Decodes entity properties only for given classes. Entities of other
+    /// classes are still created, deleted and reported to observers, but
+    /// their properties keep baseline values, so internal accessors like
+    /// [`Context::game_rules`] need their classes to be listed too.
+    pub fn decode_only_classes(&mut self, classes: &[&str]) {
+        self.decoded_classes = Some(classes.iter().map(|&x| x.into()).collect());
+    }
+
+    /// Decodes properties of all entities, which is the default.
+    pub fn decode_all_classes(&mut self) {
+        self.decoded_classes = None;
+    }
+
+    /// Entity updates store positions of the values instead of decoding
+    /// them, values are decoded on the first read. Packet entity data is kept
+    /// alive while any entity has an undecoded value from it, so memory usage
+    /// is higher. Baselines are always decoded.
+    pub fn enable_lazy_decoding(&mut self) {
+        self.lazy_decoding = true;
+    }
+
+    /// Reads outer messages on the parsing thread even though `parallel`
+    /// feature is enabled.
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    pub fn disable_parallel_reading(&mut self) {
+        self.parallel_reading = false;
+    }
+
+    /// Decodes baseline on every entity creation instead of sharing the
+    /// decoded state between entities of the class.
+    pub fn disable_baseline_cache(&mut self) {
+        self.context.baselines.cache = false;
+    }
+
+    /// Reads `DEM_FileHeader` message at the start of the replay without
+    /// parsing the rest of it.
+    pub fn header(replay: &[u8]) -> Result<CDemoFileHeader> {
+        let Some(message) = RawMessages::new(replay)?.next() else {
+            bail!("No DEM_FileHeader message")
+        };
+        let message = message?;
+        if message.msg_type()? != EDemoCommands::DemFileHeader {
+            bail!("First message is not DEM_FileHeader")
+        }
+        Ok(CDemoFileHeader::decode(message.decompressed()?.as_ref())?)
+    }
+
+    /// Reads `DEM_FileInfo` message using the offset from the replay header
+    /// without parsing the replay. Contains playback time, match id, winner
+    /// and players.
+    pub fn file_info(replay: &[u8]) -> Result<CDemoFileInfo> {
+        RawMessages::file_info(replay)
     }
 
     fn prologue(&mut self) -> Result<()> {
@@ -202,13 +682,13 @@ impl<'a> Parser<'a> {
         let mut offset: usize = 16;
         while let Some(message) = Self::read_message(&mut self.reader)? {
             self.context.tick = message.tick;
-            self.on_tick_start()?;
-            self.on_packet(message.msg_type, message.buf.as_slice())?;
-            self.on_tick_end()?;
+            self.notify_tick_start()?;
+            self.on_outer_message(&message)?;
+            self.notify_tick_end()?;
 
             offset += message.size;
 
-            if message.msg_type == EDemoCommands::DemSyncTick {
+            if message.msg_type == Some(EDemoCommands::DemSyncTick) {
                 self.prologue_completed = true;
                 self.start_offset = offset;
                 break;
@@ -221,14 +701,7 @@ impl<'a> Parser<'a> {
     /// Moves to end from current state.
     pub fn run_to_end(&mut self) -> Result<()> {
         self.prologue()?;
-
-        while let Some(message) = Self::read_message(&mut self.reader)? {
-            self.context.tick = message.tick;
-            self.on_tick_start()?;
-            self.on_packet(message.msg_type, message.buf.as_slice())?;
-            self.on_tick_end()?;
-        }
-
+        self.run_messages(None)?;
         try_observers!(self, epilogue(&self.context))
     }
 
@@ -242,40 +715,50 @@ impl<'a> Parser<'a> {
             self.context.tick = u32::MAX;
             self.context.net_tick = u32::MAX;
             self.reader.reset_to(self.start_offset);
+            for registered in self.registered.iter_mut() {
+                registered.next_interval_tick = 0;
+            }
         }
 
         self.processing_deltas = false;
 
         let observers = mem::take(&mut self.observers);
+        let registered = mem::take(&mut self.registered);
 
         let mut first_fp_checked = false;
         let mut last_fp_checked = false;
 
         while let Some(mut message) = Self::read_message(&mut self.reader)? {
+            if let Err(e) = self.check_cancelled() {
+                self.observers = observers;
+                self.registered = registered;
+                return Err(e);
+            }
             let next_fp = self.context.last_full_packet_tick == u32::MAX
                 || (target_tick - self.context.last_full_packet_tick) > 1800;
             self.context.tick = message.tick;
-            if message.msg_type == EDemoCommands::DemFullPacket {
+            if message.msg_type == Some(EDemoCommands::DemFullPacket) {
                 if next_fp && first_fp_checked {
-                    message.msg_type = EDemoCommands::DemStringTables;
-                    message.buf = CDemoFullPacket::decode(message.buf.as_slice())?
+                    message.msg_type = Some(EDemoCommands::DemStringTables);
+                    message.buf = CDemoFullPacket::decode(message.buf.as_ref())?
                         .string_table
                         .unwrap()
-                        .encode_to_vec();
+                        .encode_to_vec()
+                        .into();
                 }
 
-                self.on_packet(message.msg_type, message.buf.as_slice())?;
+                self.on_outer_message(&message)?;
             }
 
             if last_fp_checked {
-                self.on_packet(message.msg_type, message.buf.as_slice())?;
+                self.on_outer_message(&message)?;
             }
 
-            if message.msg_type == EDemoCommands::DemFullPacket && !first_fp_checked {
+            if message.msg_type == Some(EDemoCommands::DemFullPacket) && !first_fp_checked {
                 first_fp_checked = true;
             }
 
-            if message.msg_type == EDemoCommands::DemFullPacket && !next_fp {
+            if message.msg_type == Some(EDemoCommands::DemFullPacket) && !next_fp {
                 last_fp_checked = true;
                 self.processing_deltas = true;
             }
@@ -286,6 +769,7 @@ impl<'a> Parser<'a> {
         }
 
         self.observers = observers;
+        self.registered = registered;
 
         Ok(())
     }
@@ -295,13 +779,69 @@ impl<'a> Parser<'a> {
         assert!(target_tick > self.context.tick);
 
         self.prologue()?;
+        self.run_messages(Some(target_tick))
+    }
 
-        while let Some(message) = Self::read_message(&mut self.reader)? {
-            self.context.tick = message.tick;
-            self.on_tick_start()?;
-            self.on_packet(message.msg_type, message.buf.as_slice())?;
-            self.on_tick_end()?;
-            if self.context.tick >= target_tick {
+    fn process_message(&mut self, message: &OuterMessage) -> Result<()> {
+        self.context.tick = message.tick;
+        self.notify_tick_start()?;
+        match message.msg_type {
+            Some(msg_type) => self.recover(msg_type, |parser| parser.on_outer_message(message))?,
+            None => self.recover(message.cmd, |parser| parser.on_outer_message(message))?,
+        }
+        self.notify_tick_end()
+    }
+
+    /// Runs `f` and in recovery mode adds its error or panic to the report
+    /// instead of returning it.
+    fn recover<T: Debug>(
+        &mut self,
+        msg_type: T,
+        f: impl FnOnce(&mut Self) -> Result<()>,
+    ) -> Result<()> {
+        if !self.recovery || !self.prologue_completed {
+            return f(self);
+        }
+        match catch_unwind(AssertUnwindSafe(|| f(self))) {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => self
+                .report
+                .add_error(self.context.tick, &format!("{:?}", msg_type), e),
+            Err(payload) => {
+                self.report
+                    .add_panic(self.context.tick, &format!("{:?}", msg_type), payload)
+            }
+        }
+        Ok(())
+    }
+
+    fn skip_frame(&mut self, e: anyhow::Error) -> Result<()> {
+        if !self.recovery {
+            return Err(e);
+        }
+        self.report.add_error(self.context.tick, "Frame", e);
+        Ok(())
+    }
+
+    fn run_messages(&mut self, target_tick: Option<u32>) -> Result<()> {
+        #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+        if self.parallel_reading {
+            return self.run_messages_parallel(target_tick);
+        }
+
+        while self.reader.bytes_remaining() != 0 {
+            let message = match Self::read_message(&mut self.reader) {
+                Ok(Some(message)) => message,
+                Ok(None) => break,
+                Err(e) => {
+                    self.skip_frame(e)?;
+                    continue;
+                }
+            };
+            self.process_message(&message)?;
+            let offset = self.reader.buf.len() - self.reader.bytes_remaining();
+            self.on_progress(offset)?;
+            if target_tick.is_some_and(|tick| self.context.tick >= tick) {
                 break;
             }
         }
@@ -309,7 +849,92 @@ impl<'a> Parser<'a> {
         Ok(())
     }
 
-    fn read_message(reader: &mut Reader) -> Result<Option<OuterMessage>> {
+    /// Reads, decompresses and decodes outer messages on a separate thread
+    /// while current thread applies them in order. Reader is moved to the end
+    /// of the last applied message afterward, so read ahead messages are not
+    /// lost.
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    fn run_messages_parallel(&mut self, target_tick: Option<u32>) -> Result<()> {
+        let buf = self.reader.buf;
+        let start = buf.len() - self.reader.bytes_remaining();
+        let mut offset = start;
+
+        let result = std::thread::scope(|s| -> Result<()> {
+            let (tx, rx) = std::sync::mpsc::sync_channel(PIPELINE_DEPTH);
+
+            s.spawn(move || {
+                let mut reader = Reader::new(&buf[start..]);
+                loop {
+                    let remaining = reader.bytes_remaining();
+                    let Some(mut message) = Self::read_message(&mut reader).transpose() else {
+                        break;
+                    };
+                    // Decoding errors are left to the parsing thread, which
+                    // reports them in order
+                    if let Ok(message) = message.as_mut() {
+                        message.prepared = message
+                            .msg_type
+                            .and_then(|msg_type| {
+                                Prepared::decode_ahead(msg_type, &message.buf).ok()
+                            })
+                            .flatten();
+                    }
+                    if tx
+                        .send((remaining - reader.bytes_remaining(), message))
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+            });
+
+            for (size, message) in rx {
+                offset += size;
+                let message = match message {
+                    Ok(message) => message,
+                    Err(e) => {
+                        self.skip_frame(e)?;
+                        continue;
+                    }
+                };
+                self.process_message(&message)?;
+                self.on_progress(offset)?;
+                if target_tick.is_some_and(|tick| self.context.tick >= tick) {
+                    break;
+                }
+            }
+
+            Ok(())
+        });
+
+        self.reader.reset_to(offset);
+
+        result
+    }
+
+    fn on_progress(&mut self, offset: usize) -> Result<()> {
+        if let Some(f) = self.progress_callback.as_mut() {
+            f(Progress {
+                bytes: offset,
+                total: self.reader.buf.len(),
+                tick: self.context.tick,
+            });
+        }
+        self.check_cancelled()
+    }
+
+    fn check_cancelled(&self) -> Result<()> {
+        if self
+            .cancel_token
+            .as_ref()
+            .is_some_and(|token| token.is_cancelled())
+        {
+            return Err(Cancelled.into());
+        }
+        Ok(())
+    }
+
+    fn read_message<'b>(reader: &mut Reader<'b>) -> Result<Option<OuterMessage<'b>>> {
         if reader.bytes_remaining() == 0 {
             return Ok(None);
         }
@@ -322,38 +947,73 @@ impl<'a> Parser<'a> {
 
         let end = start - reader.bytes_remaining();
 
-        let msg_type = EDemoCommands::try_from(cmd & !(EDemoCommands::DemIsCompressed as i32))?;
+        // Whole frame is read before validating it, so the reader stays at
+        // the next frame on error.
+        let buf = reader.read_aligned_bytes(size);
+
         let msg_compressed = cmd & EDemoCommands::DemIsCompressed as i32 != 0;
 
         let buf = if msg_compressed {
-            let buf = reader.read_bytes(size);
             let mut decoder = snap::raw::Decoder::new();
-            decoder.decompress_vec(&buf)?
+            Cow::Owned(decoder.decompress_vec(&buf)?)
         } else {
-            reader.read_bytes(size)
+            buf
         };
 
+        let cmd = cmd & !(EDemoCommands::DemIsCompressed as i32);
+
         Ok(Some(OuterMessage {
             size: end + size as usize,
-            msg_type,
+            msg_type: EDemoCommands::try_from(cmd).ok(),
+            cmd,
             tick,
             buf,
+            prepared: None,
         }))
     }
 
+    fn on_outer_message(&mut self, message: &OuterMessage) -> Result<()> {
+        match message.msg_type {
+            Some(msg_type) => match &message.prepared {
+                Some(prepared) => self.on_prepared_packet(msg_type, prepared, &message.buf),
+                None => self.on_packet(msg_type, message.buf.as_ref()),
+            },
+            None => try_observers!(
+                self,
+                on_unknown_demo_command(&self.context, message.cmd, message.buf.as_ref())
+            ),
+        }
+    }
+
     fn on_packet(&mut self, msg_type: EDemoCommands, msg: &[u8]) -> Result<()> {
+        if let Some(prepared) = Prepared::decode(msg_type, msg)? {
+            return self.on_prepared_packet(msg_type, &prepared, msg);
+        }
+
         match msg_type {
-            EDemoCommands::DemSendTables => self.dem_send_tables(msg)?,
             EDemoCommands::DemClassInfo => self.dem_class_info(msg)?,
-            EDemoCommands::DemPacket | EDemoCommands::DemSignonPacket => self.dem_packet(msg)?,
             EDemoCommands::DemFullPacket => self.dem_full_packet(msg)?,
             EDemoCommands::DemStringTables => self.dem_string_tables(msg)?,
-            _ => {}
+            _ => try_observers!(self, on_demo_command(&self.context, msg_type, msg))?,
         };
 
         try_observers!(self, on_packet(&self.context, msg_type, msg))
     }
 
+    fn on_prepared_packet(
+        &mut self,
+        msg_type: EDemoCommands,
+        prepared: &Prepared,
+        msg: &[u8],
+    ) -> Result<()> {
+        match prepared {
+            Prepared::Packet(packet) => self.dem_packet(packet)?,
+            Prepared::SendTables(send_tables) => self.dem_send_tables(send_tables)?,
+        }
+
+        try_observers!(self, on_packet(&self.context, msg_type, msg))
+    }
+
     fn on_net_message(&mut self, msg_type: NetMessages, msg: &[u8]) -> Result<()> {
         if msg_type == NetMessages::NetTick {
             self.context.net_tick = CnetMsgTick::decode(msg)?.tick();
@@ -375,51 +1035,103 @@ impl<'a> Parser<'a> {
     }
 
     fn on_base_user_message(&mut self, msg_type: EBaseUserMessages, msg: &[u8]) -> Result<()> {
-        try_observers!(self, on_base_user_message(&self.context, msg_type, msg))
+        try_observers!(self, on_base_user_message(&self.context, msg_type, msg))?;
+        dispatch_base_user_message(&self.observers, &self.context, msg_type, msg)
     }
 
     fn on_base_game_event(&mut self, msg_type: EBaseGameEvents, msg: &[u8]) -> Result<()> {
+        match msg_type {
+            EBaseGameEvents::GeSource1LegacyGameEventList => self.game_event_list(msg)?,
+            EBaseGameEvents::GeSource1LegacyGameEvent => self.game_event(msg)?,
+            _ => {}
+        }
+
         try_observers!(self, on_base_game_event(&self.context, msg_type, msg))
     }
 
-    fn on_dota_user_message(&mut self, msg_type: EDotaUserMessages, msg: &[u8]) -> Result<()> {
-        if msg_type == EDotaUserMessages::DotaUmCombatLogDataHltv {
-            let entry = CMsgDotaCombatLogEntry::decode(msg)?;
-            self.combat_log.push_back(entry);
-        }
+    fn on_game_message(&mut self, msg_type: i32, msg: &[u8]) -> Result<()> {
+        let dispatch = Dispatch {
+            context: &self.context,
+            observers: &self.observers,
+            combat_log_types: &self.combat_log_types,
+            combat_log_mask: self.combat_log_mask,
+        };
+        self.game_messages.on_message(&dispatch, msg_type, msg)
+    }
 
-        try_observers!(self, on_dota_user_message(&self.context, msg_type, msg))
+    fn on_unknown_message(&mut self, msg_type: i32, msg: &[u8]) -> Result<()> {
+        try_observers!(self, on_unknown_message(&self.context, msg_type, msg))
     }
 
-    pub(crate) fn on_tick_start(&mut self) -> Result<()> {
+    pub(crate) fn notify_tick_start(&mut self) -> Result<()> {
         try_observers!(self, on_tick_start(&self.context))
     }
 
-    pub(crate) fn on_tick_end(&mut self) -> Result<()> {
-        if let Ok(names) = self.context.string_tables.get_by_name("CombatLogNames") {
-            while let Some(entry) = self.combat_log.pop_front() {
-                let log = CombatLog {
-                    names: &names,
-                    log: entry,
-                };
-                self.on_combat_log(&log)?;
+    pub(crate) fn notify_tick_end(&mut self) -> Result<()> {
+        let dispatch = Dispatch {
+            context: &self.context,
+            observers: &self.observers,
+            combat_log_types: &self.combat_log_types,
+            combat_log_mask: self.combat_log_mask,
+        };
+        self.game_messages.on_tick_end(&dispatch)?;
+
+        try_observers!(self, on_tick_end(&self.context))?;
+        self.notify_interval()?;
+        self.apply_observer_requests();
+        Ok(())
+    }
+
+    fn notify_interval(&mut self) -> Result<()> {
+        let tick = self.context.tick;
+        if tick == u32::MAX {
+            return Ok(());
+        }
+        for registered in self.registered.iter_mut() {
+            if registered.paused || tick < registered.next_interval_tick {
+                continue;
+            }
+            let interval = registered.observer.borrow().interval();
+            if let Some(interval) = interval {
+                registered
+                    .observer
+                    .borrow_mut()
+                    .on_interval(&self.context)?;
+                registered.next_interval_tick = tick + interval.max(1);
             }
         }
+        Ok(())
+    }
 
-        try_observers!(self, on_tick_end(&self.context))
+    fn notify_game_event(&self, event: &GameEvent) -> Result<()> {
+        try_observers!(self, on_game_event(&self.context, event))
     }
 
-    pub(crate) fn on_combat_log(&self, entry: &CombatLog) -> Result<()> {
-        try_observers!(self, on_combat_log(&self.context, entry))
+    fn on_string_table_update(&self, index: usize, modified: &[i32]) -> Result<()> {
+        let table = self.context.string_tables.get_by_id(index)?;
+        try_observers!(self, on_string_table_update(&self.context, table, modified))
     }
 
-    fn dem_send_tables(&mut self, msg: &[u8]) -> Result<()> {
-        let send_tables = CDemoSendTables::decode(msg)?;
-        let mut reader = Reader::new(send_tables.data());
-        let amount = reader.read_var_u32();
-        let buf = reader.read_bytes(amount);
+    fn dem_send_tables(&mut self, send_tables: &SendTables) -> Result<()> {
+        let schema_cache = self
+            .schema_cache
+            .as_ref()
+            .filter(|_| self.decoders.is_empty() && !self.custom_game_messages);
+        let digest = schema_cache.map(|_| Digest::new(&send_tables.data));
+        self.schema_digest = digest;
+        if let Some(serializers) = schema_cache.zip(digest).and_then(|(cache, x)| cache.get(x)) {
+            self.context.serializers = serializers;
+            return Ok(());
+        }
 
-        let fs = CsvcMsgFlattenedSerializer::decode(buf.as_slice())?;
+        let decoded;
+        let fs = match &send_tables.serializer {
+            Some(fs) => fs,
+            None => {
+                decoded = send_tables.decode_serializer()?;
+                &decoded
+            }
+        };
 
         let resolve = |p: Option<i32>| -> Box<str> {
             if let Some(i) = p {
@@ -428,25 +1140,11 @@ impl<'a> Parser<'a> {
             "".into()
         };
 
-        let pointer_types: HashSet<&'static str> = [
-            "PhysicsRagdollPose_t",
-            "CBodyComponent",
-            "CEntityIdentity",
-            "CPhysicsComponent",
-            "CRenderComponent",
-            "CDOTAGamerules",
-            "CDOTAGameManager",
-            "CDOTASpectatorGraphManager",
-            "CPlayerLocalData",
-            "CPlayer_CameraServices",
-            "CDOTAGameRules",
-        ]
-        .iter()
-        .copied()
-        .collect();
+        let pointer_types = pointer_types(self.game_messages.as_ref());
 
+        let mut serializers = Serializers::default();
         let mut fields = vec![];
-        let mut field_types = HashMap::<Box<str>, Rc<FieldType>>::default();
+        let mut field_types = HashMap::<Box<str>, Arc<FieldType>>::default();
 
         for s in fs.serializers.iter() {
             let serializer_name = fs.symbols[s.serializer_name_sym() as usize].clone();
@@ -458,23 +1156,20 @@ impl<'a> Parser<'a> {
 
                 if *i as usize >= fields.len() {
                     let var_type_str = resolve(current_field.var_type_sym);
-                    let current_field_serializer = self
-                        .context
-                        .serializers
-                        .get(&field_serializer_name)
-                        .cloned();
+                    let current_field_serializer = serializers.get(&field_serializer_name).cloned();
 
                     if !field_types.contains_key(&var_type_str) {
                         field_types.insert(
                             var_type_str.clone(),
-                            Rc::new(FieldType::new(var_type_str.clone().as_ref())),
+                            Arc::new(FieldType::new(var_type_str.clone().as_ref())),
                         );
                     }
 
                     let var_name = resolve(current_field.var_name_sym);
                     let field_type = field_types[&var_type_str].clone();
+                    let encoder = resolve(current_field.var_encoder_sym);
                     let mut properties = FieldProperties {
-                        encoder: Encoder::from_str(&resolve(current_field.var_encoder_sym)),
+                        encoder: Encoder::from_str(&encoder),
                         encoder_flags: current_field.encode_flags(),
                         bit_count: current_field.bit_count(),
                         low_value: current_field.low_value(),
@@ -505,8 +1200,9 @@ impl<'a> Parser<'a> {
                     } else if field_type.base.as_ref() == "CUtlVector"
                         || field_type.base.as_ref() == "CNetworkUtlVectorBase"
                     {
-                        FieldModel::VariableArray(Decoder::from_field(
+                        FieldModel::VariableArray(self.decoders.decoder(
                             field_type.generic.as_ref().unwrap(),
+                            &encoder,
                             properties,
                         ))
                     } else {
@@ -515,7 +1211,7 @@ impl<'a> Parser<'a> {
 
                     let decoder = match model {
                         FieldModel::Simple | FieldModel::FixedArray => {
-                            Decoder::from_field(&field_type, properties)
+                            self.decoders.decoder(&field_type, &encoder, properties)
                         }
                         FieldModel::VariableArray(_) => Decoder::Unsigned32,
                         FieldModel::FixedTable(_) => Decoder::Boolean,
@@ -526,57 +1222,87 @@ impl<'a> Parser<'a> {
                         var_name,
                         field_type,
                         model,
+                        properties,
 
                         decoder,
                     };
-                    fields.push(Rc::new(field));
+                    fields.push(Arc::new(field));
                 }
                 serializer.fields.push(fields[*i as usize].clone());
             }
-            self.context
-                .serializers
-                .insert(serializer_name.into(), Rc::new(serializer));
+            serializers.insert(serializer_name.into(), Arc::new(serializer));
         }
+
+        let serializers = Arc::new(serializers);
+        if let Some((cache, digest)) = schema_cache.zip(digest) {
+            cache.insert(digest, serializers.clone());
+        }
+        self.context.serializers = serializers;
         Ok(())
     }
 
     fn dem_class_info(&mut self, msg: &[u8]) -> Result<()> {
-        let info = CDemoClassInfo::decode(msg)?;
-        for class in info.classes {
-            let class_id = class.class_id();
-            let network_name = class.network_name();
-
-            let serializer = self.context.serializers[network_name].clone();
+        let cached = self
+            .schema_cache
+            .as_ref()
+            .zip(self.schema_digest)
+            .map(|(cache, schema)| (cache, schema, Digest::new(msg)));
 
-            let class = Rc::new(Class::new(class_id, network_name.into(), serializer));
+        let classes = match cached.and_then(|(cache, schema, info)| cache.get_classes(schema, info))
+        {
+            Some(classes) => classes,
+            None => {
+                let info = CDemoClassInfo::decode(msg)?;
+                let classes = info
+                    .classes
+                    .iter()
+                    .map(|class| {
+                        let network_name = class.network_name();
+                        let serializer = self.context.serializers[network_name].clone();
+                        Arc::new(Class::new(
+                            class.class_id(),
+                            network_name.into(),
+                            serializer,
+                        ))
+                    })
+                    .collect::<Arc<[_]>>();
+                if let Some((cache, schema, info)) = cached {
+                    cache.insert_classes(schema, info, classes.clone());
+                }
+                classes
+            }
+        };
 
+        for class in classes.iter() {
             self.context.classes.classes_vec.push(class.clone());
             self.context
                 .classes
                 .classes_by_name
-                .insert(network_name.into(), class);
+                .insert(class.name.clone(), class.clone());
         }
         Ok(())
     }
 
-    fn dem_packet(&mut self, msg: &[u8]) -> Result<()> {
-        let packet = CDemoPacket::decode(msg)?;
-        let mut packet_reader = Reader::new(packet.data());
-        while packet_reader.bytes_remaining() != 0 {
-            let msg_type = packet_reader.read_ubit_var() as i32;
-            let size = packet_reader.read_var_u32();
-            let packet_buf = packet_reader.read_bytes(size);
+    fn dem_packet(&mut self, packet: &PacketMessages) -> Result<()> {
+        for (msg_type, packet_buf) in packet.messages.iter() {
+            let msg_type = *msg_type;
 
-            if let Ok(msg) = EDotaUserMessages::try_from(msg_type) {
-                self.on_dota_user_message(msg, &packet_buf)?;
+            if let Some(name) = self.game_messages.message_name(msg_type) {
+                self.recover(format_args!("{}", name), |parser| {
+                    parser.on_game_message(msg_type, packet_buf)
+                })?;
             } else if let Ok(msg) = SvcMessages::try_from(msg_type) {
-                self.on_svc_message(msg, &packet_buf)?;
+                self.recover(msg, |parser| parser.on_svc_message(msg, packet_buf))?;
             } else if let Ok(msg) = EBaseUserMessages::try_from(msg_type) {
-                self.on_base_user_message(msg, &packet_buf)?;
+                self.recover(msg, |parser| parser.on_base_user_message(msg, packet_buf))?;
             } else if let Ok(msg) = EBaseGameEvents::try_from(msg_type) {
-                self.on_base_game_event(msg, &packet_buf)?;
+                self.recover(msg, |parser| parser.on_base_game_event(msg, packet_buf))?;
             } else if let Ok(msg) = NetMessages::try_from(msg_type) {
-                self.on_net_message(msg, &packet_buf)?;
+                self.recover(msg, |parser| parser.on_net_message(msg, packet_buf))?;
+            } else {
+                self.recover(msg_type, |parser| {
+                    parser.on_unknown_message(msg_type, packet_buf)
+                })?;
             }
         }
 
@@ -604,8 +1330,10 @@ impl<'a> Parser<'a> {
     }
 
     fn packet_entities(&mut self, msg: &[u8]) -> Result<()> {
-        let packet = CsvcMsgPacketEntities::decode(msg)?;
-        let mut entities_reader = Reader::new(packet.entity_data());
+        let mut packet = CsvcMsgPacketEntities::decode(msg)?;
+        let entity_data = Arc::new(packet.entity_data.take().unwrap_or_default());
+        let lazy_data = self.lazy_decoding.then_some(&entity_data);
+        let mut entities_reader = Reader::new(&entity_data);
 
         let updates = packet.updated_entries();
 
@@ -657,23 +1385,21 @@ impl<'a> Parser<'a> {
                         .get_by_id_rc(class_id as usize)?
                         .clone();
 
-                    if !self.context.baselines.states.contains_key(&class_id) {
-                        self.context.baselines.read_baseline(&class)
-                    }
+                    let entity_baseline = self.context.baselines.get_state(&class);
 
-                    let entity_baseline = self.context.baselines.states[&class_id].clone();
+                    let e = self.context.entities.insert(Entity::new(
+                        index,
+                        serial,
+                        class.clone(),
+                        entity_baseline,
+                    ));
 
-                    self.context.entities.entities_vec[index as usize] =
-                        Some(Entity::new(index, serial, class.clone(), entity_baseline));
-
-                    let e = self.context.entities.entities_vec[index as usize]
-                        .as_mut()
-                        .unwrap();
-
-                    self.field_reader.read_fields(
+                    Self::read_entity_fields(
+                        &mut self.field_reader,
+                        &self.decoded_classes,
                         &mut entities_reader,
-                        &e.class.serializer,
-                        &mut e.state,
+                        lazy_data,
+                        e,
                     );
 
                     op = EntityEvents::Created as isize | EntityEvents::Entered as isize;
@@ -683,10 +1409,12 @@ impl<'a> Parser<'a> {
                         .as_mut()
                         .unwrap();
 
-                    self.field_reader.read_fields(
+                    Self::read_entity_fields(
+                        &mut self.field_reader,
+                        &self.decoded_classes,
                         &mut entities_reader,
-                        &e.class.serializer,
-                        &mut e.state,
+                        lazy_data,
+                        e,
                     );
                 }
             } else {
@@ -710,23 +1438,43 @@ impl<'a> Parser<'a> {
             }
             if op & EntityEvents::Deleted as isize != 0 {
                 throw_event(&self.context, index, EntityEvents::Deleted)?;
-                self.context.entities.entities_vec[index as usize] = None;
+                self.context.entities.remove(index);
             }
         }
 
         Ok(())
     }
 
+    fn read_entity_fields(
+        field_reader: &mut FieldReader,
+        decoded_classes: &Option<HashSet<Box<str>>>,
+        reader: &mut Reader,
+        lazy_data: Option<&Arc<Vec<u8>>>,
+        entity: &mut Entity,
+    ) {
+        let serializer = &entity.class.serializer;
+        if decoded_classes
+            .as_ref()
+            .is_some_and(|x| !x.contains(entity.class.name()))
+        {
+            field_reader.skip_fields(reader, serializer);
+        } else if let Some(data) = lazy_data {
+            field_reader.read_fields_lazy(reader, serializer, &mut entity.state, data);
+        } else {
+            field_reader.read_fields(reader, serializer, &mut entity.state);
+        }
+    }
+
     fn update_string_table(&mut self, msg: &[u8]) -> Result<()> {
         let table_msg = CsvcMsgUpdateStringTable::decode(msg)?;
-        let mut table =
-            self.context.string_tables.tables[table_msg.table_id() as usize].borrow_mut();
 
-        table.parse(
+        let modified = self.context.string_tables.tables[table_msg.table_id() as usize].parse(
             &mut self.context.baselines,
             table_msg.string_data(),
             table_msg.num_changed_entries(),
-        )
+        )?;
+
+        self.on_string_table_update(table_msg.table_id() as usize, &modified)
     }
 
     fn create_string_table(&mut self, msg: &[u8]) -> Result<()> {
@@ -740,7 +1488,7 @@ impl<'a> Parser<'a> {
             user_data_size: table_msg.user_data_size(),
             flags: table_msg.flags() as u32,
             var_int_bit_counts: table_msg.using_varint_bitcounts(),
-            keys: RefCell::new(vec![String::default(); 32]),
+            keys: vec![String::default(); 32],
         };
 
         let buf = if table_msg.data_compressed() {
@@ -750,34 +1498,30 @@ impl<'a> Parser<'a> {
             table_msg.string_data().into()
         };
 
-        if table.name != "decalprecache" {
+        let modified = if table.name != "decalprecache" {
             table.parse(
                 &mut self.context.baselines,
                 buf.as_slice(),
                 table_msg.num_entries(),
-            )?;
-        }
+            )?
+        } else {
+            vec![]
+        };
 
-        let rc = Rc::new(RefCell::new(table));
-        self.context.string_tables.tables.push(rc.clone());
-        self.context
-            .string_tables
-            .name_to_table
-            .insert(rc.borrow().name.clone().into(), rc.clone());
+        let index = table.index as usize;
 
-        Ok(())
+        self.context.string_tables.push(table);
+
+        self.on_string_table_update(index, &modified)
     }
 
     fn dem_string_tables(&mut self, msg: &[u8]) -> Result<()> {
         let cmd = CDemoStringTables::decode(msg)?;
         for table in cmd.tables.iter() {
-            let mut x = self
+            let x = self
                 .context
                 .string_tables
-                .name_to_table
-                .get_mut(table.table_name())
-                .unwrap()
-                .borrow_mut();
+                .get_by_name_mut(table.table_name())?;
             if table.items.len() < x.items.len() {
                 return Ok(());
             }
@@ -786,7 +1530,7 @@ impl<'a> Parser<'a> {
             for (i, item) in table.items.iter().enumerate() {
                 x.items[i].index = i as i32;
                 x.items[i].key = item.str().to_string();
-                x.items[i].value = Rc::new(item.data().to_vec()).into();
+                x.items[i].value = Arc::new(item.data().to_vec()).into();
                 if table.table_name() == "instancebaseline" {
                     self.context.baselines.add_baseline(
                         item.str().parse()?,
@@ -794,17 +1538,42 @@ impl<'a> Parser<'a> {
                     );
                 }
             }
+
+            let index = x.index as usize;
+
+            let modified = (0..table.items.len() as i32).collect::<Vec<_>>();
+            self.on_string_table_update(index, &modified)?;
         }
 
         Ok(())
     }
 
+    fn game_event_list(&mut self, msg: &[u8]) -> Result<()> {
+        let list = CMsgSource1LegacyGameEventList::decode(msg)?;
+        self.context.game_events.update(list);
+        Ok(())
+    }
+
+    fn game_event(&self, msg: &[u8]) -> Result<()> {
+        let event = CMsgSource1LegacyGameEvent::decode(msg)?;
+        let game_event = self.context.game_events.decode_event(event)?;
+        self.notify_game_event(&game_event)
+    }
+
     fn server_info(&mut self, msg: &[u8]) -> Result<()> {
         let info = CsvcMsgServerInfo::decode(msg)?;
         self.context.classes.class_id_size =
             Some((f64::log2(info.max_classes() as f64) + 1.0) as u32);
 
-        let game_build_regexp = Regex::new(r"/dota_v(\d+)/")?;
+        let game = Game::from_game_dir(info.game_dir());
+        if game != self.context.game && !self.custom_game_messages {
+            self.game_messages = game.messages();
+        }
+        self.context.game = game;
+
+        // Game dir looks like "/opt/srcds/dota/dota_v6123/". Other Source 2
+        // games use different layouts, so build stays 0 if it can't be found.
+        let game_build_regexp = Regex::new(r"/\w+_v(\d+)/")?;
 
         if let Some(captures) = game_build_regexp.captures(info.game_dir()) {
             if let Some(build_match) = captures.get(1) {
@@ -814,8 +1583,6 @@ impl<'a> Parser<'a> {
             } else {
                 bail!("No build number found in regex capture");
             }
-        } else {
-            bail!("Failed to parse build number: '{}'", info.game_dir());
         }
         Ok(())
     }
@@ -827,6 +1594,30 @@ pub trait Observer {
         Ok(())
     }
 
+    /// Called before [`Observer::on_packet`] for outer messages the parser
+    /// doesn't handle itself, e.g. `DEM_CustomData` or `DEM_SaveGame`.
+    fn on_demo_command(
+        &mut self,
+        ctx: &Context,
+        msg_type: EDemoCommands,
+        msg: &[u8],
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called for outer messages with command id that doesn't belong to
+    /// [`EDemoCommands`]. Message is already decompressed.
+    fn on_unknown_demo_command(&mut self, ctx: &Context, cmd: i32, msg: &[u8]) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called for inner messages with type that doesn't belong to any of the
+    /// known message enums, including user messages of games other than
+    /// Dota, see [`crate::Game`].
+    fn on_unknown_message(&mut self, ctx: &Context, msg_type: i32, msg: &[u8]) -> Result<()> {
+        Ok(())
+    }
+
     fn on_net_message(&mut self, ctx: &Context, msg_type: NetMessages, msg: &[u8]) -> Result<()> {
         Ok(())
     }
@@ -862,6 +1653,12 @@ pub trait Observer {
         Ok(())
     }
 
+    /// Returns `Some(self)` to receive typed callbacks from
+    /// [`UserMessageObserver`].
+    fn user_messages(&mut self) -> Option<&mut dyn UserMessageObserver> {
+        None
+    }
+
     fn on_tick_start(&mut self, ctx: &Context) -> Result<()> {
         Ok(())
     }
@@ -878,6 +1675,40 @@ pub trait Observer {
         Ok(())
     }
 
+    fn on_game_event(&mut self, ctx: &Context, event: &GameEvent) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called after string table is created or updated. `modified` contains
+    /// indices of entries that were added or changed.
+    fn on_string_table_update(
+        &mut self,
+        ctx: &Context,
+        table: &StringTable,
+        modified: &[i32],
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Combat log types passed to [`Observer::on_combat_log`]. Checked when
+    /// the observer is registered, paused or resumed. Entries that no
+    /// observer needs are not decoded.
+    fn combat_log_types(&self) -> CombatLogTypes {
+        CombatLogTypes::ALL
+    }
+
+    /// Interval in ticks between [`Observer::on_interval`] calls, `None`
+    /// disables them. Checked again after every call, so it can be changed
+    /// between calls.
+    fn interval(&self) -> Option<u32> {
+        None
+    }
+
+    /// Called at the end of the tick once per [`Observer::interval`] ticks.
+    fn on_interval(&mut self, ctx: &Context) -> Result<()> {
+        Ok(())
+    }
+
     fn epilogue(&mut self, ctx: &Context) -> Result<()> {
         Ok(())
     }
@@ -0,0 +1,84 @@
+use crate::entity::{EHandle, Entity};
+use crate::field_value::FieldValue;
+use anyhow::Result;
+
+/// Typed accessors for `CDOTA_PlayerResource` entity. `player_id` is the
+/// index in player data, same as `m_iPlayerID` of heroes.
+pub struct PlayerResource<'a> {
+    entity: &'a Entity,
+}
+
+impl<'a> PlayerResource<'a> {
+    pub(crate) fn new(entity: &'a Entity) -> Self {
+        PlayerResource { entity }
+    }
+
+    pub fn entity(&self) -> &'a Entity {
+        self.entity
+    }
+
+    /// Number of entries in player data, including players without a team.
+    pub fn player_count(&self) -> Result<usize> {
+        self.entity.array_len("m_vecPlayerData")
+    }
+
+    pub fn steamid(&self, player_id: usize) -> Result<u64> {
+        self.player_data(player_id, "m_iPlayerSteamID")
+    }
+
+    pub fn name(&self, player_id: usize) -> Result<String> {
+        self.player_data(player_id, "m_iszPlayerName")
+    }
+
+    /// `2` for Radiant, `3` for Dire.
+    pub fn team(&self, player_id: usize) -> Result<i32> {
+        self.player_data(player_id, "m_iPlayerTeam")
+    }
+
+    /// Position of the player within the team, `0..5`.
+    pub fn team_slot(&self, player_id: usize) -> Result<i32> {
+        self.team_data(player_id, "m_iTeamSlot")
+    }
+
+    pub fn hero_id(&self, player_id: usize) -> Result<i32> {
+        self.team_data(player_id, "m_nSelectedHeroID")
+    }
+
+    pub fn selected_hero(&self, player_id: usize) -> Result<EHandle> {
+        self.team_data(player_id, "m_hSelectedHero")
+    }
+
+    pub fn level(&self, player_id: usize) -> Result<i32> {
+        self.team_data(player_id, "m_iLevel")
+    }
+
+    pub fn kills(&self, player_id: usize) -> Result<i32> {
+        self.team_data(player_id, "m_iKills")
+    }
+
+    pub fn deaths(&self, player_id: usize) -> Result<i32> {
+        self.team_data(player_id, "m_iDeaths")
+    }
+
+    pub fn assists(&self, player_id: usize) -> Result<i32> {
+        self.team_data(player_id, "m_iAssists")
+    }
+
+    fn player_data<T>(&self, player_id: usize, name: &str) -> Result<T>
+    where
+        &'a FieldValue: TryInto<T, Error = anyhow::Error>,
+    {
+        self.entity
+            .get_property_by_name(&format!("m_vecPlayerData.{player_id:04}.{name}"))?
+            .try_into()
+    }
+
+    fn team_data<T>(&self, player_id: usize, name: &str) -> Result<T>
+    where
+        &'a FieldValue: TryInto<T, Error = anyhow::Error>,
+    {
+        self.entity
+            .get_property_by_name(&format!("m_vecPlayerTeamData.{player_id:04}.{name}"))?
+            .try_into()
+    }
+}
@@ -0,0 +1,76 @@
+use crate::proto::*;
+use crate::reader::Reader;
+use anyhow::Result;
+
+/// Decoded payload of outer messages that carry other messages. With
+/// `parallel` feature payloads are decoded on the reading thread.
+pub(crate) enum Prepared {
+    Packet(PacketMessages),
+    SendTables(SendTables),
+}
+
+impl Prepared {
+    pub(crate) fn decode(msg_type: EDemoCommands, msg: &[u8]) -> Result<Option<Self>> {
+        Ok(match msg_type {
+            EDemoCommands::DemPacket | EDemoCommands::DemSignonPacket => {
+                Some(Prepared::Packet(PacketMessages::decode(msg)?))
+            }
+            EDemoCommands::DemSendTables => Some(Prepared::SendTables(SendTables::decode(msg)?)),
+            _ => None,
+        })
+    }
+
+    /// Also decodes flattened serializer of send tables. It's not used when
+    /// schema cache has the serializers, but is done off the parsing thread.
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    pub(crate) fn decode_ahead(msg_type: EDemoCommands, msg: &[u8]) -> Result<Option<Self>> {
+        let mut prepared = Self::decode(msg_type, msg)?;
+        if let Some(Prepared::SendTables(send_tables)) = prepared.as_mut() {
+            send_tables.serializer = Some(send_tables.decode_serializer()?);
+        }
+        Ok(prepared)
+    }
+}
+
+/// Inner messages of `CDemoPacket` with their type ids.
+pub(crate) struct PacketMessages {
+    pub(crate) messages: Vec<(i32, Vec<u8>)>,
+}
+
+impl PacketMessages {
+    pub(crate) fn decode(msg: &[u8]) -> Result<Self> {
+        let packet = CDemoPacket::decode(msg)?;
+        let mut reader = Reader::new(packet.data());
+        let mut messages = vec![];
+        while reader.bytes_remaining() != 0 {
+            let msg_type = reader.read_ubit_var() as i32;
+            let size = reader.read_var_u32();
+            messages.push((msg_type, reader.read_bytes(size)));
+        }
+        Ok(PacketMessages { messages })
+    }
+}
+
+/// `CDemoSendTables` with flattened serializer message. The serializer is
+/// decoded only when schema cache doesn't have it, unless it was decoded on
+/// the reading thread.
+pub(crate) struct SendTables {
+    pub(crate) data: Vec<u8>,
+    pub(crate) serializer: Option<CsvcMsgFlattenedSerializer>,
+}
+
+impl SendTables {
+    pub(crate) fn decode(msg: &[u8]) -> Result<Self> {
+        Ok(SendTables {
+            data: CDemoSendTables::decode(msg)?.data.unwrap_or_default(),
+            serializer: None,
+        })
+    }
+
+    pub(crate) fn decode_serializer(&self) -> Result<CsvcMsgFlattenedSerializer> {
+        let mut reader = Reader::new(&self.data);
+        let amount = reader.read_var_u32();
+        let buf = reader.read_bytes(amount);
+        Ok(CsvcMsgFlattenedSerializer::decode(buf.as_slice())?)
+    }
+}
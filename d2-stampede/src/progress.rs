@@ -0,0 +1,45 @@
+use std::fmt::{Display, Formatter};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Passed to the callback set with [`crate::Parser::set_progress_callback`]
+/// after every outer message.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Progress {
+    /// Bytes of the replay processed so far.
+    pub bytes: usize,
+    /// Size of the replay in bytes.
+    pub total: usize,
+    pub tick: u32,
+}
+
+/// Shared flag to stop the parser from another thread, see
+/// [`crate::Parser::set_cancel_token`].
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed)
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Error returned by the parser after [`CancelToken::cancel`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Cancelled;
+
+impl Display for Cancelled {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Parsing was cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
@@ -1,8 +1,18 @@
-use bitter::{BitReader, LittleEndianReader};
+use std::borrow::Cow;
 
+/// Little endian bit reader. Up to 63 bits are buffered in a `u64`, one
+/// refill guarantees at least 56 of them, so several small reads need only
+/// one refill and no bounds checks. Past the end of the buffer it reads
+/// zeros.
 pub(crate) struct Reader<'a> {
     pub(crate) buf: &'a [u8],
-    pub(crate) le_reader: LittleEndianReader<'a>,
+    /// Bytes not loaded into `bits` yet.
+    rest: &'a [u8],
+    /// Zero bytes loaded past the end of the buffer.
+    overrun: usize,
+    bits: u64,
+    /// Number of buffered bits that were not consumed yet.
+    avail: u32,
     string_buf: [u8; 4096],
 }
 
@@ -10,29 +20,77 @@ impl<'a> Reader<'a> {
     pub(crate) fn new(buf: &'a [u8]) -> Self {
         Reader {
             buf,
-            le_reader: LittleEndianReader::new(buf),
+            rest: buf,
+            overrun: 0,
+            bits: 0,
+            avail: 0,
             string_buf: [0; 4096],
         }
     }
 
     pub(crate) fn reset_to(&mut self, offset: usize) {
-        assert!(offset <= self.buf.len());
-        self.le_reader = LittleEndianReader::new(&self.buf[offset..])
+        self.rest = &self.buf[offset..];
+        self.overrun = 0;
+        self.bits = 0;
+        self.avail = 0;
+    }
+
+    /// Number of bits read from the start of the buffer.
+    #[inline]
+    pub(crate) fn bit_position(&self) -> usize {
+        (self.buf.len() - self.rest.len() + self.overrun) * 8 - self.avail as usize
+    }
+
+    #[inline]
+    fn bits_remaining(&self) -> usize {
+        (self.buf.len() * 8).saturating_sub(self.bit_position())
     }
 
     #[inline]
     pub(crate) fn bytes_remaining(&mut self) -> usize {
-        self.le_reader.bytes_remaining()
+        self.bits_remaining() / 8
     }
 
+    #[inline]
+    fn byte_aligned(&self) -> bool {
+        self.bit_position() & 7 == 0
+    }
+
+    /// Loads whole bytes until at least 56 bits are buffered. Bits above
+    /// `avail` already hold the next bytes, so they are overwritten with the
+    /// same values.
     #[inline]
     pub(crate) fn refill(&mut self) {
-        #[cfg(not(debug_assertions))]
-        unsafe {
-            self.le_reader.refill_lookahead_unchecked();
+        let shift = (63 - self.avail as usize) >> 3;
+        match self.rest.first_chunk() {
+            Some(bytes) => {
+                self.bits |= u64::from_le_bytes(*bytes) << self.avail;
+                self.rest = &self.rest[shift..];
+            }
+            None => self.refill_tail(shift),
         }
-        #[cfg(debug_assertions)]
-        self.le_reader.refill_lookahead();
+        self.avail |= 56;
+    }
+
+    #[cold]
+    fn refill_tail(&mut self, shift: usize) {
+        let mut bytes = [0; 8];
+        bytes[..self.rest.len()].copy_from_slice(self.rest);
+        self.bits |= u64::from_le_bytes(bytes) << self.avail;
+        let loaded = shift.min(self.rest.len());
+        self.rest = &self.rest[loaded..];
+        self.overrun += shift - loaded;
+    }
+
+    #[inline(always)]
+    fn peek(&self, amount: u32) -> u64 {
+        self.bits & ((1 << amount) - 1)
+    }
+
+    #[inline(always)]
+    fn consume(&mut self, amount: u32) {
+        self.bits >>= amount;
+        self.avail -= amount;
     }
 
     #[inline]
@@ -44,19 +102,53 @@ impl<'a> Reader<'a> {
     #[inline(always)]
     pub(crate) fn read_bits_no_refill(&mut self, amount: u32) -> u32 {
         debug_assert!(amount <= 32);
-        debug_assert!(self.le_reader.has_bits_remaining(amount as usize));
-        let x = self.le_reader.peek(amount);
-        self.le_reader.consume(amount);
-        x as u32
+        self.read_bits_fast(amount) as u32
+    }
+
+    /// Reads up to 56 bits from the buffer without refilling it, the caller
+    /// has to refill often enough.
+    #[inline(always)]
+    pub(crate) fn read_bits_fast(&mut self, amount: u32) -> u64 {
+        debug_assert!(amount <= self.avail);
+        let x = self.peek(amount);
+        self.consume(amount);
+        x
     }
 
     #[inline]
     pub(crate) fn read_bytes(&mut self, amount: u32) -> Vec<u8> {
-        let mut bytes = vec![0; amount as usize];
-        self.le_reader.read_bytes(&mut bytes);
+        if self.byte_aligned() {
+            let start = (self.bit_position() / 8).min(self.buf.len());
+            let end = (start + amount as usize).min(self.buf.len());
+            let mut bytes = self.buf[start..end].to_vec();
+            bytes.resize(amount as usize, 0);
+            self.reset_to(end);
+            return bytes;
+        }
+        let mut bytes = Vec::with_capacity(amount as usize);
+        for chunk in 0..amount.div_ceil(7) {
+            self.refill();
+            for _ in 0..(amount - chunk * 7).min(7) {
+                bytes.push(self.read_bits_fast(8) as u8);
+            }
+        }
         bytes
     }
 
+    /// Borrows the bytes from the buffer when the reader is byte aligned,
+    /// which is always the case for outer messages, and copies them
+    /// otherwise.
+    #[inline]
+    pub(crate) fn read_aligned_bytes(&mut self, amount: u32) -> Cow<'a, [u8]> {
+        if !self.byte_aligned() {
+            return Cow::Owned(self.read_bytes(amount));
+        }
+        let start = (self.bit_position() / 8).min(self.buf.len());
+        let end = (start + amount as usize).min(self.buf.len());
+        self.reset_to(end);
+        Cow::Borrowed(&self.buf[start..end])
+    }
+
     #[inline]
     pub(crate) fn read_bool(&mut self) -> bool {
         // self.refill();
@@ -68,21 +160,25 @@ impl<'a> Reader<'a> {
         f32::from_bits(self.read_bits(32))
     }
 
+    /// Reads up to 5 bytes of varint at once.
     #[inline]
     pub(crate) fn read_var_u32(&mut self) -> u32 {
-        let mut x: u32 = 0;
-        let mut y: u32 = 0;
         self.refill();
-        loop {
-            let byte = self.read_bits_no_refill(8);
-
-            x |= (byte & 0x7F) << y;
-            y += 7;
-
-            if (byte & 0x80) == 0 || y == 35 {
-                return x;
-            }
-        }
+        let bits = self.peek(40);
+        let stop = !bits & 0x80_8080_8080;
+        let len = if stop == 0 {
+            5
+        } else {
+            stop.trailing_zeros() / 8 + 1
+        };
+        self.consume(len * 8);
+        let bits = bits & ((1u64 << (len * 8)) - 1);
+        let x = (bits & 0x7f)
+            | ((bits >> 1) & (0x7f << 7))
+            | ((bits >> 2) & (0x7f << 14))
+            | ((bits >> 3) & (0x7f << 21))
+            | ((bits >> 4) & (0x7f << 28));
+        x as u32
     }
 
     #[inline]
@@ -116,15 +212,14 @@ impl<'a> Reader<'a> {
     }
 
     const UBV_COUNT: [u8; 4] = [0, 4, 8, 28];
+    /// Reads 6 bit header and the rest of the value at once, the header
+    /// tells the length of the rest.
     #[inline]
     pub(crate) fn read_ubit_var(&mut self) -> u32 {
         self.refill();
-        let a = self.read_bits_no_refill(6);
-        let b = a >> 4;
-        if a == 0 {
-            return b;
-        }
-        (a & 15) | (self.read_bits_no_refill(Self::UBV_COUNT[b as usize] as u32) << 4)
+        let count = Self::UBV_COUNT[(self.peek(6) >> 4) as usize] as u32;
+        let x = self.read_bits_fast(6 + count);
+        ((x & 15) | ((x >> 6) << 4)) as u32
     }
 
     const UBVFP_COUNT: [u8; 5] = [2, 4, 10, 17, 31];
@@ -184,19 +279,38 @@ impl<'a> Reader<'a> {
 
     #[inline]
     pub(crate) fn read_le_u64(&mut self) -> u64 {
-        self.le_reader.read_u64().unwrap()
+        self.refill();
+        let low = self.read_bits_fast(32);
+        self.refill();
+        low | (self.read_bits_fast(32) << 32)
     }
 
+    /// Refills once per 7 bytes instead of every byte.
     #[inline]
     pub(crate) fn read_string(&mut self) -> String {
         let mut i = 0;
         loop {
-            let b = self.read_bits(8) as u8;
-            if b == 0 {
-                return String::from_utf8_lossy(&self.string_buf[..i]).into();
+            self.refill();
+            for _ in 0..7 {
+                let b = self.read_bits_no_refill(8) as u8;
+                if b == 0 {
+                    return String::from_utf8_lossy(&self.string_buf[..i]).into();
+                }
+                self.string_buf[i] = b;
+                i += 1;
+            }
+        }
+    }
+
+    #[inline]
+    pub(crate) fn skip_string(&mut self) {
+        loop {
+            self.refill();
+            for _ in 0..7 {
+                if self.read_bits_no_refill(8) == 0 {
+                    return;
+                }
             }
-            self.string_buf[i] = b;
-            i += 1;
         }
     }
 
@@ -238,8 +352,7 @@ impl<'a> Reader<'a> {
     #[inline]
     pub(crate) fn read_bits_as_bytes(&mut self, n: u32) -> Vec<u8> {
         let bits = n % 8;
-        let mut tmp = vec![0; (n >> 3) as usize];
-        self.le_reader.read_bytes(&mut tmp);
+        let mut tmp = self.read_bytes(n >> 3);
         if bits > 0 {
             tmp.push(self.read_bits(bits) as u8);
         }
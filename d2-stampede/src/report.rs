@@ -0,0 +1,86 @@
+use hashbrown::HashMap;
+use prettytable::{row, Table};
+use std::any::Any;
+use std::fmt::{Display, Formatter};
+
+/// Message skipped by the parser in recovery mode.
+#[derive(Debug, Clone)]
+pub struct SkippedMessage {
+    pub tick: u32,
+    /// Message type, e.g. `SvcPacketEntities`, or `Frame` for outer messages
+    /// that couldn't be read.
+    pub category: Box<str>,
+    pub error: Box<str>,
+    /// True if the error was a panic.
+    pub panicked: bool,
+}
+
+/// Messages skipped by the parser in recovery mode, see
+/// [`crate::Parser::enable_recovery`].
+#[derive(Debug, Clone, Default)]
+pub struct ParseReport {
+    skipped: Vec<SkippedMessage>,
+}
+
+impl ParseReport {
+    pub fn is_empty(&self) -> bool {
+        self.skipped.is_empty()
+    }
+
+    pub fn skipped(&self) -> &[SkippedMessage] {
+        &self.skipped
+    }
+
+    /// Sorted ticks with skipped messages.
+    pub fn ticks(&self) -> Vec<u32> {
+        let mut ticks = self.skipped.iter().map(|x| x.tick).collect::<Vec<_>>();
+        ticks.sort_unstable();
+        ticks.dedup();
+        ticks
+    }
+
+    /// Number of skipped messages by category.
+    pub fn categories(&self) -> HashMap<&str, usize> {
+        let mut categories = HashMap::default();
+        for message in self.skipped.iter() {
+            *categories.entry(message.category.as_ref()).or_default() += 1;
+        }
+        categories
+    }
+
+    pub(crate) fn add_error(&mut self, tick: u32, category: &str, error: anyhow::Error) {
+        self.skipped.push(SkippedMessage {
+            tick,
+            category: category.into(),
+            error: format!("{:#}", error).into(),
+            panicked: false,
+        })
+    }
+
+    pub(crate) fn add_panic(&mut self, tick: u32, category: &str, payload: Box<dyn Any + Send>) {
+        let error = if let Some(x) = payload.downcast_ref::<&str>() {
+            (*x).into()
+        } else if let Some(x) = payload.downcast_ref::<String>() {
+            x.as_str().into()
+        } else {
+            "Unknown panic".into()
+        };
+        self.skipped.push(SkippedMessage {
+            tick,
+            category: category.into(),
+            error,
+            panicked: true,
+        })
+    }
+}
+
+impl Display for ParseReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut table = Table::new();
+        table.add_row(row!["Tick", "Category", "Error"]);
+        for message in self.skipped.iter() {
+            table.add_row(row![message.tick, message.category, message.error]);
+        }
+        write!(f, "{}", table)
+    }
+}
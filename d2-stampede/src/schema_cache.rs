@@ -0,0 +1,117 @@
+use crate::class::Class;
+use crate::serializer::Serializer;
+use hashbrown::HashMap;
+use std::hash::{DefaultHasher, Hasher};
+use std::sync::{Arc, Mutex};
+
+pub(crate) type Serializers = HashMap<Box<str>, Arc<Serializer>>;
+
+type Schemas = HashMap<Digest, Schema>;
+
+/// 128-bit digest of send tables or class info, so the cache doesn't keep
+/// megabytes of raw data per game build. It's not cryptographic, cache
+/// expects replays from the game server rather than crafted ones.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub(crate) struct Digest(u64, u64);
+
+impl Digest {
+    pub(crate) fn new(data: &[u8]) -> Self {
+        let mut first = DefaultHasher::new();
+        first.write(data);
+        let mut second = DefaultHasher::new();
+        second.write_usize(data.len());
+        second.write(data);
+        Digest(first.finish(), second.finish())
+    }
+}
+
+struct Schema {
+    serializers: Arc<Serializers>,
+    /// Classes built from class info with these serializers, keyed by
+    /// digest of class info.
+    classes: HashMap<Digest, Arc<[Arc<Class>]>>,
+}
+
+/// Serializers and classes decoded from send tables and class info, shared
+/// between parsers. Replays from the same game build have identical send
+/// tables, so when parsing many replays only the first one has to build
+/// field decoders:
+///
+/// ```ignore
+/// let cache = SchemaCache::new();
+/// replays.par_iter().try_for_each(|replay| {
+///     let mut parser = Parser::new(replay)?;
+///     parser.set_schema_cache(cache.clone());
+///     parser.run_to_end()
+/// })?;
+/// ```
+///
+/// Cache is keyed by 128-bit digest of the send tables, so replays from
+/// different builds can share one cache. Cloning is cheap and the cache can
+/// be used from multiple threads.
+#[derive(Clone, Default)]
+pub struct SchemaCache {
+    schemas: Arc<Mutex<Schemas>>,
+}
+
+impl SchemaCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of distinct send tables in the cache.
+    pub fn len(&self) -> usize {
+        self.schemas.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        self.schemas.lock().unwrap().clear()
+    }
+
+    pub(crate) fn get(&self, send_tables: Digest) -> Option<Arc<Serializers>> {
+        self.schemas
+            .lock()
+            .unwrap()
+            .get(&send_tables)
+            .map(|schema| schema.serializers.clone())
+    }
+
+    pub(crate) fn insert(&self, send_tables: Digest, serializers: Arc<Serializers>) {
+        self.schemas.lock().unwrap().insert(
+            send_tables,
+            Schema {
+                serializers,
+                classes: HashMap::default(),
+            },
+        );
+    }
+
+    pub(crate) fn get_classes(
+        &self,
+        send_tables: Digest,
+        class_info: Digest,
+    ) -> Option<Arc<[Arc<Class>]>> {
+        self.schemas
+            .lock()
+            .unwrap()
+            .get(&send_tables)?
+            .classes
+            .get(&class_info)
+            .cloned()
+    }
+
+    pub(crate) fn insert_classes(
+        &self,
+        send_tables: Digest,
+        class_info: Digest,
+        classes: Arc<[Arc<Class>]>,
+    ) {
+        if let Some(schema) = self.schemas.lock().unwrap().get_mut(&send_tables) {
+            schema.classes.insert(class_info, classes);
+        }
+    }
+}
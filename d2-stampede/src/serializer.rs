@@ -1,21 +1,20 @@
+use crate::class::{FieldInfo, FieldKind};
 use crate::decoder::Decoder;
 use crate::field::{Field, FieldModel, FieldPath, FieldType, FieldVector};
 use anyhow::{bail, Result};
 use hashbrown::HashMap;
-use std::cell::RefCell;
-use std::rc::Rc;
+use std::sync::{Arc, RwLock};
 
-#[derive(Clone)]
 pub(crate) struct Serializer {
-    pub(crate) fields: Vec<Rc<Field>>,
-    pub(crate) fp_cache: RefCell<HashMap<Box<str>, FieldPath>>,
+    pub(crate) fields: Vec<Arc<Field>>,
+    pub(crate) fp_cache: RwLock<HashMap<Box<str>, FieldPath>>,
 }
 
 impl Serializer {
     pub(crate) fn new() -> Self {
         Serializer {
             fields: vec![],
-            fp_cache: RefCell::new(HashMap::default()),
+            fp_cache: RwLock::new(HashMap::default()),
         }
     }
 
@@ -127,47 +126,83 @@ impl Serializer {
 
     #[inline(always)]
     pub(crate) fn get_field_path_for_name(&self, name: &str) -> Result<FieldPath> {
-        if !self.fp_cache.borrow().contains_key(name) {
-            let mut current_serializer = self;
-            let mut fp = FieldPath::new();
-            let mut offset = 0;
-            'outer: loop {
-                for (i, f) in current_serializer.fields.iter().enumerate() {
-                    if &name[offset..] == f.var_name.as_ref() {
-                        fp.path[fp.last] = i as u8;
-                        break 'outer;
-                    }
-                    if name[offset..].as_bytes().get(f.var_name.len()) == Some(&b"."[0])
-                        && &name[offset..(offset + f.var_name.len())] == f.var_name.as_ref()
-                    {
-                        fp.path[fp.last] = i as u8;
-                        fp.last += 1;
-                        offset += f.var_name.len() + 1;
-                        match &f.model {
-                            FieldModel::FixedArray | FieldModel::VariableArray(_) => {
-                                fp.path[fp.last] = name[offset..].parse::<u8>()?;
-                                break 'outer;
-                            }
-                            FieldModel::FixedTable(serializer) => {
-                                current_serializer = serializer;
-                                continue 'outer;
-                            }
-                            FieldModel::VariableTable(serializer) => {
-                                fp.path[fp.last] = name[offset..(offset + 4)].parse::<u8>()?;
-                                fp.last += 1;
-                                offset += 5;
-                                current_serializer = serializer;
-                                continue 'outer;
-                            }
-                            FieldModel::Simple => unreachable!(),
+        if let Some(fp) = self.fp_cache.read().unwrap().get(name) {
+            return Ok(*fp);
+        }
+
+        let mut current_serializer = self;
+        let mut fp = FieldPath::new();
+        let mut offset = 0;
+        'outer: loop {
+            for (i, f) in current_serializer.fields.iter().enumerate() {
+                if &name[offset..] == f.var_name.as_ref() {
+                    fp.path[fp.last] = i as u8;
+                    break 'outer;
+                }
+                if name.as_bytes().get(offset + f.var_name.len()) == Some(&b"."[0])
+                    && &name[offset..(offset + f.var_name.len())] == f.var_name.as_ref()
+                {
+                    fp.path[fp.last] = i as u8;
+                    fp.last += 1;
+                    offset += f.var_name.len() + 1;
+                    match &f.model {
+                        FieldModel::FixedArray | FieldModel::VariableArray(_) => {
+                            fp.path[fp.last] = name[offset..].parse::<u8>()?;
+                            break 'outer;
+                        }
+                        FieldModel::FixedTable(serializer) => {
+                            current_serializer = serializer;
+                            continue 'outer;
                         }
+                        FieldModel::VariableTable(serializer) => {
+                            fp.path[fp.last] = name[offset..(offset + 4)].parse::<u8>()?;
+                            fp.last += 1;
+                            offset += 5;
+                            current_serializer = serializer;
+                            continue 'outer;
+                        }
+                        FieldModel::Simple => unreachable!(),
                     }
                 }
-                bail!("No field path for given name \"{}\"", name)
             }
-            self.fp_cache.borrow_mut().insert(name.into(), fp);
+            bail!("No field path for given name \"{}\"", name)
+        }
+        self.fp_cache.write().unwrap().insert(name.into(), fp);
+        Ok(fp)
+    }
+
+    pub(crate) fn get_field_infos(&self, prefix: &str, out: &mut Vec<FieldInfo>) {
+        for field in self.fields.iter() {
+            let name = format!("{}{}", prefix, field.var_name);
+            let kind = match &field.model {
+                FieldModel::Simple => FieldKind::Simple,
+                FieldModel::FixedArray => {
+                    FieldKind::FixedArray(field.field_type.count.unwrap_or_default())
+                }
+                FieldModel::VariableArray(_) => FieldKind::VariableArray,
+                FieldModel::FixedTable(_) => FieldKind::FixedTable,
+                FieldModel::VariableTable(_) => FieldKind::VariableTable,
+            };
+            out.push(FieldInfo {
+                name: name.clone(),
+                field_type: field.field_type.as_string(),
+                kind,
+                encoder: field.properties.encoder,
+                encoder_flags: field.properties.encoder_flags,
+                bit_count: field.properties.bit_count,
+                low_value: field.properties.low_value,
+                high_value: field.properties.high_value,
+            });
+            match &field.model {
+                FieldModel::FixedTable(serializer) => {
+                    serializer.get_field_infos(&format!("{}.", name), out)
+                }
+                FieldModel::VariableTable(serializer) => {
+                    serializer.get_field_infos(&format!("{}.*.", name), out)
+                }
+                _ => {}
+            }
         }
-        Ok(self.fp_cache.borrow()[name])
     }
 
     pub(crate) fn get_field_paths<'a>(
@@ -0,0 +1,114 @@
+use crate::entity::{Entities, Entity};
+use crate::field_value::FieldValue;
+use hashbrown::HashMap;
+
+/// Owned copy of all entities, see [`Entities::snapshot`]. Unchanged entity
+/// states are shared with the parser, so keeping snapshots of every minute
+/// of the game is cheap.
+#[derive(Clone)]
+pub struct Snapshot {
+    entities: Entities,
+}
+
+impl Snapshot {
+    pub(crate) fn new(entities: Entities) -> Self {
+        Snapshot { entities }
+    }
+
+    pub fn entities(&self) -> &Entities {
+        &self.entities
+    }
+
+    /// Changes from this snapshot to `other`. Entity with the same index but
+    /// different serial or class is reported as deleted and created.
+    pub fn diff(&self, other: &Snapshot) -> SnapshotDiff {
+        let mut diff = SnapshotDiff::default();
+
+        let len = self
+            .entities
+            .entities_vec
+            .len()
+            .max(other.entities.entities_vec.len());
+        for index in 0..len {
+            let old = self.entities.get_by_index(index).ok();
+            let new = other.entities.get_by_index(index).ok();
+            match (old, new) {
+                (Some(old), Some(new)) if Self::same_entity(old, new) => {
+                    if old.state.ptr_eq(&new.state) {
+                        continue;
+                    }
+                    let change = Self::entity_change(old, new);
+                    if !change.is_empty() {
+                        diff.changed.push(change);
+                    }
+                }
+                (old, new) => {
+                    if let Some(old) = old {
+                        diff.deleted.push(old.index());
+                    }
+                    if let Some(new) = new {
+                        diff.created.push(new.index());
+                    }
+                }
+            }
+        }
+
+        diff
+    }
+
+    fn same_entity(old: &Entity, new: &Entity) -> bool {
+        old.serial() == new.serial() && old.class().id() == new.class().id()
+    }
+
+    fn entity_change(old: &Entity, new: &Entity) -> EntityChange {
+        let mut old_properties = old.properties().collect::<HashMap<String, &FieldValue>>();
+        let mut change = EntityChange {
+            index: new.index(),
+            added: vec![],
+            removed: vec![],
+            changed: vec![],
+        };
+        for (name, value) in new.properties() {
+            match old_properties.remove(&name) {
+                Some(old_value) if old_value == value => {}
+                Some(_) => change.changed.push(name),
+                None => change.added.push(name),
+            }
+        }
+        change.removed.extend(old_properties.into_keys());
+        change
+    }
+}
+
+/// Result of [`Snapshot::diff`]. Entities are identified by index, which can
+/// be looked up in the corresponding snapshot.
+#[derive(Debug, Clone, Default)]
+pub struct SnapshotDiff {
+    pub created: Vec<u32>,
+    pub deleted: Vec<u32>,
+    pub changed: Vec<EntityChange>,
+}
+
+impl SnapshotDiff {
+    pub fn is_empty(&self) -> bool {
+        self.created.is_empty() && self.deleted.is_empty() && self.changed.is_empty()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EntityChange {
+    pub index: u32,
+    /// Properties that only exist in the newer snapshot, e.g. new array
+    /// elements.
+    pub added: Vec<String>,
+    /// Properties that only exist in the older snapshot.
+    pub removed: Vec<String>,
+    /// Properties with different values.
+    pub changed: Vec<String>,
+}
+
+impl EntityChange {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
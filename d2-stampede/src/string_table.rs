@@ -3,13 +3,12 @@ use crate::reader::Reader;
 use anyhow::{anyhow, Context, Result};
 use hashbrown::HashMap;
 use prettytable::{row, Table};
-use std::cell::{Ref, RefCell};
 use std::fmt::{Display, Formatter};
-use std::rc::Rc;
+use std::sync::Arc;
 
 pub struct StringTables {
-    pub(crate) tables: Vec<Rc<RefCell<StringTable>>>,
-    pub(crate) name_to_table: HashMap<Box<str>, Rc<RefCell<StringTable>>>,
+    pub(crate) tables: Vec<StringTable>,
+    pub(crate) name_to_table: HashMap<Box<str>, usize>,
 }
 
 impl StringTables {
@@ -20,22 +19,34 @@ impl StringTables {
         }
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = Ref<StringTable>> {
-        self.tables.iter().map(|table| table.borrow())
+    pub fn iter(&self) -> impl Iterator<Item = &StringTable> {
+        self.tables.iter()
     }
 
-    pub fn get_by_id(&self, id: usize) -> Result<Ref<StringTable>> {
+    pub fn get_by_id(&self, id: usize) -> Result<&StringTable> {
         self.tables
             .get(id)
             .with_context(|| anyhow!("No string table for given id"))
-            .map(|table| table.borrow())
     }
 
-    pub fn get_by_name(&self, name: &str) -> Result<Ref<StringTable>> {
+    pub fn get_by_name(&self, name: &str) -> Result<&StringTable> {
         self.name_to_table
             .get(name)
             .with_context(|| anyhow!("No string table for given name"))
-            .map(|table| table.borrow())
+            .map(|&id| &self.tables[id])
+    }
+
+    pub(crate) fn get_by_name_mut(&mut self, name: &str) -> Result<&mut StringTable> {
+        self.name_to_table
+            .get(name)
+            .with_context(|| anyhow!("No string table for given name"))
+            .map(|&id| &mut self.tables[id])
+    }
+
+    pub(crate) fn push(&mut self, table: StringTable) {
+        self.name_to_table
+            .insert(table.name.as_str().into(), self.tables.len());
+        self.tables.push(table);
     }
 }
 
@@ -43,11 +54,11 @@ impl StringTables {
 pub struct StringTableEntry {
     pub(crate) index: i32,
     pub(crate) key: String,
-    pub(crate) value: Option<Rc<Vec<u8>>>,
+    pub(crate) value: Option<Arc<Vec<u8>>>,
 }
 
 impl StringTableEntry {
-    pub(crate) fn new(index: i32, key: String, value: Option<Rc<Vec<u8>>>) -> Self {
+    pub(crate) fn new(index: i32, key: String, value: Option<Arc<Vec<u8>>>) -> Self {
         StringTableEntry { index, key, value }
     }
 
@@ -73,7 +84,7 @@ pub struct StringTable {
     pub(crate) user_data_size: i32,
     pub(crate) flags: u32,
     pub(crate) var_int_bit_counts: bool,
-    pub(crate) keys: RefCell<Vec<String>>,
+    pub(crate) keys: Vec<String>,
 }
 
 impl StringTable {
@@ -95,17 +106,26 @@ impl StringTable {
             .with_context(|| anyhow!("No string table entry for given index {idx}"))
     }
 
+    pub fn get_entry_by_key(&self, key: &str) -> Result<&StringTableEntry> {
+        self.items
+            .iter()
+            .find(|entry| entry.key == key)
+            .with_context(|| anyhow!("No string table entry for given key \"{key}\""))
+    }
+
+    /// Parses string table update and returns indices of modified entries.
     pub(crate) fn parse(
         &mut self,
         baselines: &mut Baselines,
         buf: &[u8],
         num_updates: i32,
-    ) -> Result<()> {
+    ) -> Result<Vec<i32>> {
         let items = &mut self.items;
+        let mut modified = Vec::with_capacity(num_updates as usize);
         let mut r = Reader::new(buf);
         let mut index = -1;
         let mut delta_pos = 0;
-        let mut keys = self.keys.borrow_mut();
+        let keys = &mut self.keys;
 
         for _ in 0..num_updates {
             r.refill();
@@ -150,7 +170,7 @@ impl StringTable {
                     }
                 };
 
-                let value = Rc::new(if is_compressed {
+                let value = Arc::new(if is_compressed {
                     let mut decoder = snap::raw::Decoder::new();
                     decoder
                         .decompress_vec(&r.read_bits_as_bytes(bit_size))
@@ -174,9 +194,11 @@ impl StringTable {
             } else {
                 items.push(StringTableEntry::new(index, key.unwrap(), value));
             }
+
+            modified.push(index);
         }
 
-        Ok(())
+        Ok(modified)
     }
 }
 
@@ -0,0 +1,350 @@
+use crate::parser::{Context, Observer};
+use crate::proto::*;
+use anyhow::Result;
+use std::cell::{OnceCell, RefCell};
+use std::rc::Rc;
+
+/// Message passed to [`UserMessageObserver`] methods. It's decoded on the
+/// first [`LazyMessage::get`] call and the result is shared between
+/// observers, so messages nobody reads are never decoded.
+pub struct LazyMessage<'m, M> {
+    bytes: &'m [u8],
+    decoded: OnceCell<M>,
+}
+
+impl<'m, M: Message + Default> LazyMessage<'m, M> {
+    pub(crate) fn new(bytes: &'m [u8]) -> Self {
+        LazyMessage {
+            bytes,
+            decoded: OnceCell::new(),
+        }
+    }
+
+    pub fn get(&self) -> Result<&M> {
+        if let Some(msg) = self.decoded.get() {
+            return Ok(msg);
+        }
+        let msg = M::decode(self.bytes)?;
+        Ok(self.decoded.get_or_init(|| msg))
+    }
+
+    pub fn bytes(&self) -> &'m [u8] {
+        self.bytes
+    }
+}
+
+fn dispatch<'a, M>(
+    observers: &[Rc<RefCell<dyn Observer + 'a>>],
+    msg: &LazyMessage<M>,
+    f: impl Fn(&mut dyn UserMessageObserver, &LazyMessage<M>) -> Result<()>,
+) -> Result<()> {
+    for obs in observers {
+        let mut obs = obs.borrow_mut();
+        if let Some(obs) = obs.user_messages() {
+            f(obs, msg)?;
+        }
+    }
+    Ok(())
+}
+
+macro_rules! user_messages {
+    (
+        dota { $($dota_variant:ident => $dota_method:ident($dota_type:ty),)* }
+        base { $($base_variant:ident => $base_method:ident($base_type:ty),)* }
+    ) => {
+        /// Typed callbacks for DOTA and base user messages. Observer has to
+        /// return itself from [`Observer::user_messages`] to receive them:
+        ///
+        /// ```ignore
+        /// impl Observer for Chat {
+        ///     fn user_messages(&mut self) -> Option<&mut dyn UserMessageObserver> {
+        ///         Some(self)
+        ///     }
+        /// }
+        ///
+        /// impl UserMessageObserver for Chat {
+        ///     fn on_chat_message(
+        ///         &mut self,
+        ///         ctx: &Context,
+        ///         msg: &LazyMessage<CdotaUserMsgChatMessage>,
+        ///     ) -> Result<()> {
+        ///         println!("{}", msg.get()?.message_text());
+        ///         Ok(())
+        ///     }
+        /// }
+        /// ```
+        ///
+        /// Messages without protobuf definition are only passed to
+        /// [`Observer::on_dota_user_message`] and
+        /// [`Observer::on_base_user_message`].
+        #[allow(unused_variables)]
+        pub trait UserMessageObserver {
+            $(
+                fn $dota_method(
+                    &mut self,
+                    ctx: &Context,
+                    msg: &LazyMessage<$dota_type>,
+                ) -> Result<()> {
+                    Ok(())
+                }
+            )*
+
+            $(
+                fn $base_method(
+                    &mut self,
+                    ctx: &Context,
+                    msg: &LazyMessage<$base_type>,
+                ) -> Result<()> {
+                    Ok(())
+                }
+            )*
+        }
+
+        pub(crate) fn dispatch_dota_user_message<'a>(
+            observers: &[Rc<RefCell<dyn Observer + 'a>>],
+            ctx: &Context,
+            msg_type: EDotaUserMessages,
+            msg: &[u8],
+        ) -> Result<()> {
+            match msg_type {
+                $(
+                    EDotaUserMessages::$dota_variant => dispatch(
+                        observers,
+                        &LazyMessage::<$dota_type>::new(msg),
+                        |obs, msg| obs.$dota_method(ctx, msg),
+                    ),
+                )*
+                _ => Ok(()),
+            }
+        }
+
+        pub(crate) fn dispatch_base_user_message<'a>(
+            observers: &[Rc<RefCell<dyn Observer + 'a>>],
+            ctx: &Context,
+            msg_type: EBaseUserMessages,
+            msg: &[u8],
+        ) -> Result<()> {
+            match msg_type {
+                $(
+                    EBaseUserMessages::$base_variant => dispatch(
+                        observers,
+                        &LazyMessage::<$base_type>::new(msg),
+                        |obs, msg| obs.$base_method(ctx, msg),
+                    ),
+                )*
+                _ => Ok(()),
+            }
+        }
+    };
+}
+
+// Message types follow protobuf naming, e.g. `DOTA_UM_ChatMessage` is
+// `CDOTAUserMsg_ChatMessage`. Base messages that share the name with DOTA
+// ones have `base` prefix.
+user_messages! {
+    dota {
+        DotaUmAiDebugLine => on_ai_debug_line(CdotaUserMsgAiDebugLine),
+        DotaUmChatEvent => on_chat_event(CdotaUserMsgChatEvent),
+        DotaUmCombatHeroPositions => on_combat_hero_positions(CdotaUserMsgCombatHeroPositions),
+        DotaUmCombatLogData => on_combat_log_data(CMsgDotaCombatLogEntry),
+        DotaUmCombatLogBulkData => on_combat_log_bulk_data(CdotaUserMsgCombatLogBulkData),
+        DotaUmCreateLinearProjectile => on_create_linear_projectile(CdotaUserMsgCreateLinearProjectile),
+        DotaUmDestroyLinearProjectile => on_destroy_linear_projectile(CdotaUserMsgDestroyLinearProjectile),
+        DotaUmDodgeTrackingProjectiles => on_dodge_tracking_projectiles(CdotaUserMsgDodgeTrackingProjectiles),
+        DotaUmGlobalLightColor => on_global_light_color(CdotaUserMsgGlobalLightColor),
+        DotaUmGlobalLightDirection => on_global_light_direction(CdotaUserMsgGlobalLightDirection),
+        DotaUmInvalidCommand => on_invalid_command(CdotaUserMsgInvalidCommand),
+        DotaUmLocationPing => on_location_ping(CdotaUserMsgLocationPing),
+        DotaUmMapLine => on_map_line(CdotaUserMsgMapLine),
+        DotaUmMiniKillCamInfo => on_mini_kill_cam_info(CdotaUserMsgMiniKillCamInfo),
+        DotaUmMinimapDebugPoint => on_minimap_debug_point(CdotaUserMsgMinimapDebugPoint),
+        DotaUmMinimapEvent => on_minimap_event(CdotaUserMsgMinimapEvent),
+        DotaUmNevermoreRequiem => on_nevermore_requiem(CdotaUserMsgNevermoreRequiem),
+        DotaUmOverheadEvent => on_overhead_event(CdotaUserMsgOverheadEvent),
+        DotaUmSetNextAutobuyItem => on_set_next_autobuy_item(CdotaUserMsgSetNextAutobuyItem),
+        DotaUmSharedCooldown => on_shared_cooldown(CdotaUserMsgSharedCooldown),
+        DotaUmSpectatorPlayerClick => on_spectator_player_click(CdotaUserMsgSpectatorPlayerClick),
+        DotaUmTutorialTipInfo => on_tutorial_tip_info(CdotaUserMsgTutorialTipInfo),
+        DotaUmUnitEvent => on_unit_event(CdotaUserMsgUnitEvent),
+        DotaUmParticleManager => on_particle_manager(CUserMsgParticleManager),
+        DotaUmBotChat => on_bot_chat(CdotaUserMsgBotChat),
+        DotaUmHudError => on_hud_error(CdotaUserMsgHudError),
+        DotaUmItemPurchased => on_item_purchased(CdotaUserMsgItemPurchased),
+        DotaUmPing => on_ping(CdotaUserMsgPing),
+        DotaUmItemFound => on_item_found(CdotaUserMsgItemFound),
+        DotaUmSwapVerify => on_swap_verify(CdotaUserMsgSwapVerify),
+        DotaUmWorldLine => on_world_line(CdotaUserMsgWorldLine),
+        DotaUmItemAlert => on_item_alert(CdotaUserMsgItemAlert),
+        DotaUmHalloweenDrops => on_halloween_drops(CdotaUserMsgHalloweenDrops),
+        DotaUmChatWheel => on_chat_wheel(CdotaUserMsgChatWheel),
+        DotaUmReceivedXmasGift => on_received_xmas_gift(CdotaUserMsgReceivedXmasGift),
+        DotaUmUpdateSharedContent => on_update_shared_content(CdotaUserMsgUpdateSharedContent),
+        DotaUmTutorialRequestExp => on_tutorial_request_exp(CdotaUserMsgTutorialRequestExp),
+        DotaUmTutorialPingMinimap => on_tutorial_ping_minimap(CdotaUserMsgTutorialPingMinimap),
+        DotaUmGamerulesStateChanged => on_gamerules_state_changed(CdotaUserMsgGamerulesStateChanged),
+        DotaUmShowSurvey => on_show_survey(CdotaUserMsgShowSurvey),
+        DotaUmTutorialFade => on_tutorial_fade(CdotaUserMsgTutorialFade),
+        DotaUmAddQuestLogEntry => on_add_quest_log_entry(CdotaUserMsgAddQuestLogEntry),
+        DotaUmSendStatPopup => on_send_stat_popup(CdotaUserMsgSendStatPopup),
+        DotaUmTutorialFinish => on_tutorial_finish(CdotaUserMsgTutorialFinish),
+        DotaUmSendRoshanPopup => on_send_roshan_popup(CdotaUserMsgSendRoshanPopup),
+        DotaUmSendGenericToolTip => on_send_generic_tool_tip(CdotaUserMsgSendGenericToolTip),
+        DotaUmSendFinalGold => on_send_final_gold(CdotaUserMsgSendFinalGold),
+        DotaUmCustomMsg => on_custom_msg(CdotaUserMsgCustomMsg),
+        DotaUmCoachHudPing => on_coach_hud_ping(CdotaUserMsgCoachHudPing),
+        DotaUmClientLoadGridNav => on_client_load_grid_nav(CdotaUserMsgClientLoadGridNav),
+        DotaUmTeProjectile => on_te_projectile(CdotaUserMsgTeProjectile),
+        DotaUmTeProjectileLoc => on_te_projectile_loc(CdotaUserMsgTeProjectileLoc),
+        DotaUmTeDotaBloodImpact => on_te_dota_blood_impact(CdotaUserMsgTeDotaBloodImpact),
+        DotaUmTeUnitAnimation => on_te_unit_animation(CdotaUserMsgTeUnitAnimation),
+        DotaUmTeUnitAnimationEnd => on_te_unit_animation_end(CdotaUserMsgTeUnitAnimationEnd),
+        DotaUmAbilityPing => on_ability_ping(CdotaUserMsgAbilityPing),
+        DotaUmShowGenericPopup => on_show_generic_popup(CdotaUserMsgShowGenericPopup),
+        DotaUmVoteStart => on_vote_start(CdotaUserMsgVoteStart),
+        DotaUmVoteUpdate => on_vote_update(CdotaUserMsgVoteUpdate),
+        DotaUmVoteEnd => on_vote_end(CdotaUserMsgVoteEnd),
+        DotaUmBoosterState => on_booster_state(CdotaUserMsgBoosterState),
+        DotaUmWillPurchaseAlert => on_will_purchase_alert(CdotaUserMsgWillPurchaseAlert),
+        DotaUmTutorialMinimapPosition => on_tutorial_minimap_position(CdotaUserMsgTutorialMinimapPosition),
+        DotaUmPlayerMmr => on_player_mmr(CdotaUserMsgPlayerMmr),
+        DotaUmAbilitySteal => on_ability_steal(CdotaUserMsgAbilitySteal),
+        DotaUmCourierKilledAlert => on_courier_killed_alert(CdotaUserMsgCourierKilledAlert),
+        DotaUmEnemyItemAlert => on_enemy_item_alert(CdotaUserMsgEnemyItemAlert),
+        DotaUmStatsMatchDetails => on_stats_match_details(CdotaUserMsgStatsMatchDetails),
+        DotaUmMiniTaunt => on_mini_taunt(CdotaUserMsgMiniTaunt),
+        DotaUmBuyBackStateAlert => on_buy_back_state_alert(CdotaUserMsgBuyBackStateAlert),
+        DotaUmSpeechBubble => on_speech_bubble(CdotaUserMsgSpeechBubble),
+        DotaUmCustomHeaderMessage => on_custom_header_message(CdotaUserMsgCustomHeaderMessage),
+        DotaUmQuickBuyAlert => on_quick_buy_alert(CdotaUserMsgQuickBuyAlert),
+        DotaUmPredictionResult => on_prediction_result(CdotaUserMsgPredictionResult),
+        DotaUmModifierAlert => on_modifier_alert(CdotaUserMsgModifierAlert),
+        DotaUmHpManaAlert => on_hp_mana_alert(CdotaUserMsgHpManaAlert),
+        DotaUmGlyphAlert => on_glyph_alert(CdotaUserMsgGlyphAlert),
+        DotaUmBeastChat => on_beast_chat(CdotaUserMsgBeastChat),
+        DotaUmSpectatorPlayerUnitOrders => on_spectator_player_unit_orders(CdotaUserMsgSpectatorPlayerUnitOrders),
+        DotaUmCustomHudElementCreate => on_custom_hud_element_create(CdotaUserMsgCustomHudElementCreate),
+        DotaUmCustomHudElementModify => on_custom_hud_element_modify(CdotaUserMsgCustomHudElementModify),
+        DotaUmCustomHudElementDestroy => on_custom_hud_element_destroy(CdotaUserMsgCustomHudElementDestroy),
+        DotaUmCompendiumState => on_compendium_state(CdotaUserMsgCompendiumState),
+        DotaUmProjectionAbility => on_projection_ability(CdotaUserMsgProjectionAbility),
+        DotaUmProjectionEvent => on_projection_event(CdotaUserMsgProjectionEvent),
+        DotaUmCombatLogDataHltv => on_combat_log_data_hltv(CMsgDotaCombatLogEntry),
+        DotaUmXpAlert => on_xp_alert(CdotaUserMsgXpAlert),
+        DotaUmUpdateQuestProgress => on_update_quest_progress(CdotaUserMsgUpdateQuestProgress),
+        DotaUmQuestStatus => on_quest_status(CdotaUserMsgQuestStatus),
+        DotaUmSuggestHeroPick => on_suggest_hero_pick(CdotaUserMsgSuggestHeroPick),
+        DotaUmSuggestHeroRole => on_suggest_hero_role(CdotaUserMsgSuggestHeroRole),
+        DotaUmKillcamDamageTaken => on_killcam_damage_taken(CdotaUserMsgKillcamDamageTaken),
+        DotaUmSelectPenaltyGold => on_select_penalty_gold(CdotaUserMsgSelectPenaltyGold),
+        DotaUmRollDiceResult => on_roll_dice_result(CdotaUserMsgRollDiceResult),
+        DotaUmFlipCoinResult => on_flip_coin_result(CdotaUserMsgFlipCoinResult),
+        DotaUmRequestItemSuggestions => on_request_item_suggestions(CdotaUserMessageRequestItemSuggestions),
+        DotaUmTeamCaptainChanged => on_team_captain_changed(CdotaUserMessageTeamCaptainChanged),
+        DotaUmSendRoshanSpectatorPhase => on_send_roshan_spectator_phase(CdotaUserMsgSendRoshanSpectatorPhase),
+        DotaUmChatWheelCooldown => on_chat_wheel_cooldown(CdotaUserMsgChatWheelCooldown),
+        DotaUmDismissAllStatPopups => on_dismiss_all_stat_popups(CdotaUserMsgDismissAllStatPopups),
+        DotaUmTeDestroyProjectile => on_te_destroy_projectile(CdotaUserMsgTeDestroyProjectile),
+        DotaUmHeroRelicProgress => on_hero_relic_progress(CdotaUserMsgHeroRelicProgress),
+        DotaUmAbilityDraftRequestAbility => on_ability_draft_request_ability(CdotaUserMsgAbilityDraftRequestAbility),
+        DotaUmItemSold => on_item_sold(CdotaUserMsgItemSold),
+        DotaUmDamageReport => on_damage_report(CdotaUserMsgDamageReport),
+        DotaUmSalutePlayer => on_salute_player(CdotaUserMsgSalutePlayer),
+        DotaUmTipAlert => on_tip_alert(CdotaUserMsgTipAlert),
+        DotaUmReplaceQueryUnit => on_replace_query_unit(CdotaUserMsgReplaceQueryUnit),
+        DotaUmEmptyTeleportAlert => on_empty_teleport_alert(CdotaUserMsgEmptyTeleportAlert),
+        DotaUmMarsArenaOfBloodAttack => on_mars_arena_of_blood_attack(CdotaUserMsgMarsArenaOfBloodAttack),
+        DotaUmEsArcanaCombo => on_es_arcana_combo(CdotaUserMsgEsArcanaCombo),
+        DotaUmEsArcanaComboSummary => on_es_arcana_combo_summary(CdotaUserMsgEsArcanaComboSummary),
+        DotaUmHighFiveLeftHanging => on_high_five_left_hanging(CdotaUserMsgHighFiveLeftHanging),
+        DotaUmHighFiveCompleted => on_high_five_completed(CdotaUserMsgHighFiveCompleted),
+        DotaUmShovelUnearth => on_shovel_unearth(CdotaUserMsgShovelUnearth),
+        DotaEmInvokerSpellCast => on_invoker_spell_cast(CdotaEntityMsgInvokerSpellCast),
+        DotaUmRadarAlert => on_radar_alert(CdotaUserMsgRadarAlert),
+        DotaUmAllStarEvent => on_all_star_event(CdotaUserMsgAllStarEvent),
+        DotaUmTalentTreeAlert => on_talent_tree_alert(CdotaUserMsgTalentTreeAlert),
+        DotaUmQueuedOrderRemoved => on_queued_order_removed(CdotaUserMsgQueuedOrderRemoved),
+        DotaUmDebugChallenge => on_debug_challenge(CdotaUserMsgDebugChallenge),
+        DotaUmOmArcanaCombo => on_om_arcana_combo(CdotaUserMsgOmArcanaCombo),
+        DotaUmFoundNeutralItem => on_found_neutral_item(CdotaUserMsgFoundNeutralItem),
+        DotaUmOutpostCaptured => on_outpost_captured(CdotaUserMsgOutpostCaptured),
+        DotaUmOutpostGrantedXp => on_outpost_granted_xp(CdotaUserMsgOutpostGrantedXp),
+        DotaUmMoveCameraToUnit => on_move_camera_to_unit(CdotaUserMsgMoveCameraToUnit),
+        DotaUmPauseMinigameData => on_pause_minigame_data(CdotaUserMsgPauseMinigameData),
+        DotaUmVersusScenePlayerBehavior => on_versus_scene_player_behavior(CdotaUserMsgVersusScenePlayerBehavior),
+        DotaUmQoPArcanaSummary => on_qop_arcana_summary(CdotaUserMsgQoPArcanaSummary),
+        DotaUmHotPotatoCreated => on_hot_potato_created(CdotaUserMsgHotPotatoCreated),
+        DotaUmHotPotatoExploded => on_hot_potato_exploded(CdotaUserMsgHotPotatoExploded),
+        DotaUmWkArcanaProgress => on_wk_arcana_progress(CdotaUserMsgWkArcanaProgress),
+        DotaUmGuildChallengeProgress => on_guild_challenge_progress(CdotaUserMsgGuildChallengeProgress),
+        DotaUmWrArcanaProgress => on_wr_arcana_progress(CdotaUserMsgWrArcanaProgress),
+        DotaUmWrArcanaSummary => on_wr_arcana_summary(CdotaUserMsgWrArcanaSummary),
+        DotaUmEmptyItemSlotAlert => on_empty_item_slot_alert(CdotaUserMsgEmptyItemSlotAlert),
+        DotaUmAghsStatusAlert => on_aghs_status_alert(CdotaUserMsgAghsStatusAlert),
+        DotaUmPingConfirmation => on_ping_confirmation(CdotaUserMsgPingConfirmation),
+        DotaUmMutedPlayers => on_muted_players(CdotaUserMsgMutedPlayers),
+        DotaUmContextualTip => on_contextual_tip(CdotaUserMsgContextualTip),
+        DotaUmChatMessage => on_chat_message(CdotaUserMsgChatMessage),
+        DotaUmNeutralCampAlert => on_neutral_camp_alert(CdotaUserMsgNeutralCampAlert),
+        DotaUmRockPaperScissorsStarted => on_rock_paper_scissors_started(CdotaUserMsgRockPaperScissorsStarted),
+        DotaUmRockPaperScissorsFinished => on_rock_paper_scissors_finished(CdotaUserMsgRockPaperScissorsFinished),
+        DotaUmDuelOpponentKilled => on_duel_opponent_killed(CdotaUserMsgDuelOpponentKilled),
+        DotaUmDuelAccepted => on_duel_accepted(CdotaUserMsgDuelAccepted),
+        DotaUmDuelRequested => on_duel_requested(CdotaUserMsgDuelRequested),
+        DotaUmMuertaReleaseEventAssignedTargetKilled => on_muerta_release_event_assigned_target_killed(CdotaUserMsgMuertaReleaseEventAssignedTargetKilled),
+        DotaUmPlayerDraftSuggestPick => on_player_draft_suggest_pick(CdotaUserMsgPlayerDraftSuggestPick),
+        DotaUmPlayerDraftPick => on_player_draft_pick(CdotaUserMsgPlayerDraftPick),
+        DotaUmUpdateLinearProjectileCpData => on_update_linear_projectile_cp_data(CdotaUserMsgUpdateLinearProjectileCpData),
+        DotaUmGiftPlayer => on_gift_player(CdotaUserMsgGiftPlayer),
+        DotaUmFacetPing => on_facet_ping(CdotaUserMsgFacetPing),
+        DotaUmInnatePing => on_innate_ping(CdotaUserMsgInnatePing),
+    }
+    base {
+        UmAchievementEvent => on_achievement_event(CUserMessageAchievementEvent),
+        UmCloseCaption => on_close_caption(CUserMessageCloseCaption),
+        UmCloseCaptionDirect => on_close_caption_direct(CUserMessageCloseCaptionDirect),
+        UmCurrentTimescale => on_current_timescale(CUserMessageCurrentTimescale),
+        UmDesiredTimescale => on_desired_timescale(CUserMessageDesiredTimescale),
+        UmFade => on_fade(CUserMessageFade),
+        UmGameTitle => on_game_title(CUserMessageGameTitle),
+        UmHudMsg => on_hud_msg(CUserMessageHudMsg),
+        UmHudText => on_hud_text(CUserMessageHudText),
+        UmColoredText => on_colored_text(CUserMessageColoredText),
+        UmRequestState => on_request_state(CUserMessageRequestState),
+        UmResetHud => on_reset_hud(CUserMessageResetHud),
+        UmRumble => on_rumble(CUserMessageRumble),
+        UmSayText => on_say_text(CUserMessageSayText),
+        UmSayText2 => on_say_text2(CUserMessageSayText2),
+        UmSayTextChannel => on_say_text_channel(CUserMessageSayTextChannel),
+        UmShake => on_shake(CUserMessageShake),
+        UmShakeDir => on_shake_dir(CUserMessageShakeDir),
+        UmWaterShake => on_water_shake(CUserMessageWaterShake),
+        UmTextMsg => on_text_msg(CUserMessageTextMsg),
+        UmScreenTilt => on_screen_tilt(CUserMessageScreenTilt),
+        UmVoiceMask => on_voice_mask(CUserMessageVoiceMask),
+        UmSendAudio => on_send_audio(CUserMessageSendAudio),
+        UmItemPickup => on_item_pickup(CUserMessageItemPickup),
+        UmAmmoDenied => on_ammo_denied(CUserMessageAmmoDenied),
+        UmShowMenu => on_show_menu(CUserMessageShowMenu),
+        UmCreditsMsg => on_credits_msg(CUserMessageCreditsMsg),
+        UmCloseCaptionPlaceholder => on_close_caption_placeholder(CUserMessageCloseCaptionPlaceholder),
+        UmCameraTransition => on_camera_transition(CUserMessageCameraTransition),
+        UmAudioParameter => on_audio_parameter(CUserMessageAudioParameter),
+        UmParticleManager => on_base_particle_manager(CUserMsgParticleManager),
+        UmHudError => on_base_hud_error(CUserMsgHudError),
+        UmCustomGameEvent => on_custom_game_event(CUserMsgCustomGameEvent),
+        UmHapticsManagerPulse => on_haptics_manager_pulse(CUserMessageHapticsManagerPulse),
+        UmHapticsManagerEffect => on_haptics_manager_effect(CUserMessageHapticsManagerEffect),
+        UmUpdateCssClasses => on_update_css_classes(CUserMessageUpdateCssClasses),
+        UmServerFrameTime => on_server_frame_time(CUserMessageServerFrameTime),
+        UmLagCompensationError => on_lag_compensation_error(CUserMessageLagCompensationError),
+        UmRequestDllStatus => on_request_dll_status(CUserMessageRequestDllStatus),
+        UmRequestUtilAction => on_request_util_action(CUserMessageRequestUtilAction),
+        UmRequestInventory => on_request_inventory(CUserMessageRequestInventory),
+        UmInventoryResponse => on_inventory_response(CUserMessageInventoryResponse),
+        UmRequestDiagnostic => on_request_diagnostic(CUserMessageRequestDiagnostic),
+        UmDiagnosticResponse => on_diagnostic_response(CUserMessageDiagnosticResponse),
+        UmExtraUserData => on_extra_user_data(CUserMessageExtraUserData),
+        UmNotifyResponseFound => on_notify_response_found(CUserMessageNotifyResponseFound),
+        UmPlayResponseConditional => on_play_response_conditional(CUserMessagePlayResponseConditional),
+    }
+}
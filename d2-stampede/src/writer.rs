@@ -0,0 +1,287 @@
+use crate::game_rules::TICKS_PER_SECOND;
+use crate::proto::{CDemoFileInfo, EDemoCommands, Message};
+use crate::reader::Reader;
+use anyhow::{bail, Result};
+use std::borrow::Cow;
+use std::io::{Seek, SeekFrom, Write};
+
+const HEADER: &[u8; 8] = b"PBDEMS2\0";
+
+/// Outer message as stored in the replay, payload is not decompressed.
+pub struct RawMessage<'a> {
+    cmd: i32,
+    tick: u32,
+    data: &'a [u8],
+}
+
+impl<'a> RawMessage<'a> {
+    pub fn msg_type(&self) -> Result<EDemoCommands> {
+        Ok(EDemoCommands::try_from(
+            self.cmd & !(EDemoCommands::DemIsCompressed as i32),
+        )?)
+    }
+
+    pub fn tick(&self) -> u32 {
+        self.tick
+    }
+
+    pub fn is_compressed(&self) -> bool {
+        self.cmd & EDemoCommands::DemIsCompressed as i32 != 0
+    }
+
+    /// Payload as stored in the replay.
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    pub fn decompressed(&self) -> Result<Cow<'a, [u8]>> {
+        if self.is_compressed() {
+            Ok(Cow::Owned(
+                snap::raw::Decoder::new().decompress_vec(self.data)?,
+            ))
+        } else {
+            Ok(Cow::Borrowed(self.data))
+        }
+    }
+}
+
+/// Iterator over outer messages of the replay without decoding them.
+pub struct RawMessages<'a> {
+    reader: Reader<'a>,
+    buf: &'a [u8],
+}
+
+impl<'a> RawMessages<'a> {
+    pub fn new(replay: &'a [u8]) -> Result<Self> {
+        if replay.len() < 16 || &replay[..8] != HEADER {
+            bail!("Supports only Source 2 replays")
+        }
+        Ok(Self::from_offset(replay, 16))
+    }
+
+    fn from_offset(replay: &'a [u8], offset: usize) -> Self {
+        let buf = &replay[offset..];
+        RawMessages {
+            reader: Reader::new(buf),
+            buf,
+        }
+    }
+
+    /// `DEM_FileInfo` message referenced by the header.
+    pub fn file_info(replay: &'a [u8]) -> Result<CDemoFileInfo> {
+        if replay.len() < 16 {
+            bail!("Couldn't validate file header")
+        }
+        let offset = u32::from_le_bytes(replay[8..12].try_into()?) as usize;
+        if replay.len() < offset {
+            bail!("Buf is too small")
+        }
+        let Some(message) = Self::from_offset(replay, offset).next() else {
+            bail!("No DEM_FileInfo message")
+        };
+        Ok(CDemoFileInfo::decode(message?.decompressed()?.as_ref())?)
+    }
+
+    fn next_message(&mut self) -> Result<RawMessage<'a>> {
+        let cmd = self.reader.read_var_u32() as i32;
+        let tick = self.reader.read_var_u32();
+        let size = self.reader.read_var_u32() as usize;
+
+        let start = self.buf.len() - self.reader.bytes_remaining();
+        if start + size > self.buf.len() {
+            bail!("Message at {} is out of bounds", start);
+        }
+        self.reader.reset_to(start + size);
+
+        Ok(RawMessage {
+            cmd,
+            tick,
+            data: &self.buf[start..start + size],
+        })
+    }
+}
+
+impl<'a> Iterator for RawMessages<'a> {
+    type Item = Result<RawMessage<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.reader.bytes_remaining() == 0 {
+            return None;
+        }
+        Some(self.next_message())
+    }
+}
+
+fn write_var_u32(buf: &mut Vec<u8>, mut value: u32) {
+    while value >= 0x80 {
+        buf.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+/// Writes outer messages into a new replay. Offset of the `DEM_FileInfo`
+/// message is written into the header by [`DemoWriter::finish`].
+pub struct DemoWriter<W: Write + Seek> {
+    writer: W,
+    offset: u64,
+    file_info_offset: Option<u64>,
+}
+
+impl<W: Write + Seek> DemoWriter<W> {
+    pub fn new(mut writer: W) -> Result<Self> {
+        writer.write_all(HEADER)?;
+        writer.write_all(&[0; 8])?;
+        Ok(DemoWriter {
+            writer,
+            offset: 16,
+            file_info_offset: None,
+        })
+    }
+
+    /// Copies message as is.
+    pub fn write_raw(&mut self, message: &RawMessage) -> Result<()> {
+        if message.msg_type()? == EDemoCommands::DemFileInfo {
+            self.file_info_offset = Some(self.offset);
+        }
+        self.write(message.cmd, message.tick, message.data)
+    }
+
+    /// Writes message with given payload, compressing it if `compress` is
+    /// set.
+    pub fn write_message(
+        &mut self,
+        msg_type: EDemoCommands,
+        tick: u32,
+        payload: &[u8],
+        compress: bool,
+    ) -> Result<()> {
+        if msg_type == EDemoCommands::DemFileInfo {
+            self.file_info_offset = Some(self.offset);
+        }
+        if compress {
+            let data = snap::raw::Encoder::new().compress_vec(payload)?;
+            self.write(
+                msg_type as i32 | EDemoCommands::DemIsCompressed as i32,
+                tick,
+                &data,
+            )
+        } else {
+            self.write(msg_type as i32, tick, payload)
+        }
+    }
+
+    fn write(&mut self, cmd: i32, tick: u32, data: &[u8]) -> Result<()> {
+        let mut buf = Vec::with_capacity(15);
+        write_var_u32(&mut buf, cmd as u32);
+        write_var_u32(&mut buf, tick);
+        write_var_u32(&mut buf, data.len() as u32);
+        self.writer.write_all(&buf)?;
+        self.writer.write_all(data)?;
+        self.offset += (buf.len() + data.len()) as u64;
+        Ok(())
+    }
+
+    /// Fixes up the header and returns the underlying writer.
+    pub fn finish(mut self) -> Result<W> {
+        let Some(offset) = self.file_info_offset else {
+            bail!("Replay has no DEM_FileInfo message")
+        };
+        self.writer.seek(SeekFrom::Start(8))?;
+        self.writer.write_all(&(offset as u32).to_le_bytes())?;
+        self.writer.seek(SeekFrom::End(0))?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Copies replay keeping only messages between `from_tick` and `to_tick`.
+/// Signon messages up to `DEM_SyncTick` are always kept, the kept range
+/// starts at the last full packet before `from_tick`, so the result can be
+/// parsed from the beginning. Playback time in `DEM_FileInfo` is updated to
+/// the kept range.
+pub fn trim_replay<W: Write + Seek>(
+    replay: &[u8],
+    from_tick: u32,
+    to_tick: u32,
+    writer: W,
+) -> Result<W> {
+    if from_tick > to_tick {
+        bail!("Invalid tick range {}..{}", from_tick, to_tick);
+    }
+
+    let mut anchor_tick = None;
+    let mut synced = false;
+    for message in RawMessages::new(replay)? {
+        let message = message?;
+        match message.msg_type()? {
+            EDemoCommands::DemSyncTick => synced = true,
+            EDemoCommands::DemFullPacket if synced && message.tick() <= from_tick => {
+                anchor_tick = Some(message.tick())
+            }
+            _ => {}
+        }
+        if synced && message.tick() > from_tick {
+            break;
+        }
+    }
+
+    let mut file_info = RawMessages::file_info(replay)?;
+    let mut writer = DemoWriter::new(writer)?;
+    let mut first_tick = None;
+    let mut last_tick = 0;
+    let mut synced = false;
+    let mut anchored = false;
+
+    for message in RawMessages::new(replay)? {
+        let message = message?;
+        let msg_type = message.msg_type()?;
+
+        if msg_type == EDemoCommands::DemFileInfo || msg_type == EDemoCommands::DemStop {
+            continue;
+        }
+
+        if !synced {
+            writer.write_raw(&message)?;
+            synced = msg_type == EDemoCommands::DemSyncTick;
+            continue;
+        }
+
+        if message.tick() > to_tick {
+            break;
+        }
+
+        if !anchored {
+            anchored = match anchor_tick {
+                Some(tick) => msg_type == EDemoCommands::DemFullPacket && message.tick() == tick,
+                None => true,
+            };
+        }
+
+        if anchored {
+            writer.write_raw(&message)?;
+            first_tick.get_or_insert(message.tick());
+            last_tick = message.tick();
+        }
+    }
+
+    writer.write_message(EDemoCommands::DemStop, last_tick, &[], false)?;
+
+    let ticks = last_tick.saturating_sub(first_tick.unwrap_or(last_tick));
+    if file_info.playback_ticks() > 0 {
+        file_info.playback_frames = Some(
+            (file_info.playback_frames() as i64 * ticks as i64 / file_info.playback_ticks() as i64)
+                as i32,
+        );
+    }
+    file_info.playback_ticks = Some(ticks as i32);
+    file_info.playback_time = Some(ticks as f32 / TICKS_PER_SECOND);
+    writer.write_message(
+        EDemoCommands::DemFileInfo,
+        last_tick,
+        &file_info.encode_to_vec(),
+        false,
+    )?;
+
+    writer.finish()
+}